@@ -1,21 +1,47 @@
 use proc_macro::TokenStream as TokenStream1;
-use syn::{ parse_macro_input, DeriveInput, Data, DataStruct, DataEnum, DataUnion, Fields, FieldsNamed, FieldsUnnamed, Field, Index };
+use proc_macro2::TokenStream;
+use syn::{ parse_macro_input, parse_quote, DeriveInput, Data, DataStruct, DataEnum, DataUnion, Fields, FieldsNamed, FieldsUnnamed, Field, Generics, GenericParam, Index, Member, Path };
 use syn::spanned::Spanned;
-use quote::{ quote, quote_spanned };
+use quote::{ quote, quote_spanned, format_ident };
 
 
 
-#[proc_macro_derive(Component)]
+#[proc_macro_derive(Component, attributes(component))]
 pub fn derive_component(input : TokenStream1) -> TokenStream1 {
     let DeriveInput {
         ident,
         generics,
+        attrs,
         ..
     } = parse_macro_input!(input as DeriveInput);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let mut on_add    = None;
+    let mut on_remove = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+        let result = attr.parse_nested_meta(|meta| {
+            let hook = if (meta.path.is_ident("on_add")) { &mut on_add }
+                else if (meta.path.is_ident("on_remove")) { &mut on_remove }
+                else if (meta.path.is_ident("on_insert")) { return Err(meta.error("components are only inserted when an entity is spawned, use `on_add` instead")); }
+                else { return Err(meta.error("expected `on_add` or `on_remove`")); };
+            let path = meta.value()?.parse::<Path>()?;
+            *hook = Some(quote_spanned!{ path.span() =>
+                Some(|cmds, entity| axecs::component::__box_hook(#path(cmds, entity)))
+            });
+            Ok(())
+        });
+        if let Err(err) = result {
+            return err.to_compile_error().into();
+        }
+    }
+    let on_add    = on_add    .map(|on_add    | quote!{ const ON_ADD    : Option<axecs::component::ComponentHook> = #on_add;    });
+    let on_remove = on_remove .map(|on_remove | quote!{ const ON_REMOVE : Option<axecs::component::ComponentHook> = #on_remove; });
+
     quote!{
-        impl #impl_generics axecs::component::Component for #ident #ty_generics #where_clause { }
+        impl #impl_generics axecs::component::Component for #ident #ty_generics #where_clause {
+            #on_add
+            #on_remove
+        }
     }.into()
 }
 
@@ -115,6 +141,185 @@ pub fn derive_bundle(input : TokenStream1) -> TokenStream1 {
 
 
 
+#[proc_macro_derive(ComponentQuery)]
+pub fn derive_component_query(input : TokenStream1) -> TokenStream1 {
+    let DeriveInput {
+        vis,
+        ident,
+        generics,
+        data,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+
+    if let Some(lifetime) = generics.lifetimes().next() {
+        let span = lifetime.span();
+        return quote_spanned!{ span => compile_error!("`ComponentQuery` can not be derived for items with lifetime parameters. Use `'static` references instead"); }.into();
+    }
+
+    match (data) {
+
+
+        Data::Struct(DataStruct { fields : Fields::Named(FieldsNamed { named, .. }), .. }) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+            let mut item_generics = generics.clone();
+            item_generics.params.insert(0, parse_quote!{ 'item });
+            let (item_impl_generics, _, _) = item_generics.split_for_impl();
+            let item_ty_params = generics.params.iter().filter_map(|param| match (param) {
+                GenericParam::Type(param)  => Some(&param.ident),
+                GenericParam::Const(param) => Some(&param.ident),
+                GenericParam::Lifetime(_)  => None
+            }).collect::<Vec<_>>();
+
+            let item_ident     = format_ident!("{}Item", ident);
+            let item_mut_ident = format_ident!("{}ItemMut", ident);
+
+            let mut field_visibilities = Vec::with_capacity(named.len());
+            let mut field_names        = Vec::with_capacity(named.len());
+            let mut field_types        = Vec::with_capacity(named.len());
+            for Field { vis, ident, ty, .. } in named {
+                let field_span = ty.span();
+                field_visibilities .push(vis);
+                field_names        .push(ident.unwrap());
+                field_types        .push(quote_spanned!{ field_span => #ty });
+            }
+            let fields_type    = nested_tuple(&field_types);
+            let fields_pattern = nested_tuple(&field_names.iter().map(|name| quote!{ #name }).collect::<Vec<_>>());
+
+            let read_only_generics = read_only_generics(&generics, &field_types, quote!{ axecs::component::query::ReadOnlyComponentQuery });
+            let read_only_where    = &read_only_generics.where_clause;
+
+            quote!{
+
+                #[doc = concat!("The item returned when [`", stringify!(#ident), "`] is iterated immutably.")]
+                #vis struct #item_ident #item_impl_generics #where_clause {
+                    #( #field_visibilities #field_names : <#field_types as axecs::component::query::ComponentQuery>::Item<'item> , )*
+                }
+
+                #[doc = concat!("The item returned when [`", stringify!(#ident), "`] is iterated mutably.")]
+                #vis struct #item_mut_ident #item_impl_generics #where_clause {
+                    #( #field_visibilities #field_names : <#field_types as axecs::component::query::ComponentQuery>::ItemMut<'item> , )*
+                }
+
+                unsafe impl #impl_generics axecs::component::query::ComponentQuery for #ident #ty_generics #where_clause {
+                    type Item<'item> = #item_ident<'item, #( #item_ty_params , )*>;
+                    type ItemMut<'item> = #item_mut_ident<'item, #( #item_ty_params , )*>;
+                    type AsStatic = Self;
+
+                    fn is_subset_of_archetype(column_types : &[::core::any::TypeId]) -> bool {
+                        <#fields_type as axecs::component::query::ComponentQuery>::is_subset_of_archetype(column_types)
+                    }
+
+                    unsafe fn get_row_ref<'item>(archetype : &'item axecs::component::archetype::Archetype, row : usize) -> axecs::query::QueryAcquireResult<Self::Item<'item>> {
+                        unsafe{ <#fields_type as axecs::component::query::ComponentQuery>::get_row_ref(archetype, row) }
+                            .map(|#fields_pattern| #item_ident { #( #field_names , )* })
+                    }
+
+                    unsafe fn get_row_mut<'item>(archetype : &'item axecs::component::archetype::Archetype, row : usize) -> axecs::query::QueryAcquireResult<Self::ItemMut<'item>> {
+                        unsafe{ <#fields_type as axecs::component::query::ComponentQuery>::get_row_mut(archetype, row) }
+                            .map(|#fields_pattern| #item_mut_ident { #( #field_names , )* })
+                    }
+
+                    fn validate() -> axecs::query::QueryValidator {
+                        <#fields_type as axecs::component::query::ComponentQuery>::validate()
+                    }
+
+                }
+
+                unsafe impl #impl_generics axecs::component::query::ReadOnlyComponentQuery for #ident #ty_generics #read_only_where { }
+
+            }
+        },
+
+
+        Data::Struct(DataStruct { struct_token, .. }) => {
+            let span = struct_token.span;
+            quote_spanned!{ span => compile_error!("`ComponentQuery` can only be derived for structs with named fields"); }
+        },
+        Data::Enum(DataEnum { enum_token, .. }) => {
+            let span = enum_token.span;
+            quote_spanned!{ span => compile_error!("`ComponentQuery` can not be derived for enums"); }
+        },
+        Data::Union(DataUnion { union_token, .. }) => {
+            let span = union_token.span;
+            quote_spanned!{ span => compile_error!("`ComponentQuery` can not be derived for unions"); }
+        }
+
+    }.into()
+}
+
+
+
+#[proc_macro_derive(Query)]
+pub fn derive_query(input : TokenStream1) -> TokenStream1 {
+    let DeriveInput {
+        ident,
+        generics,
+        data,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    match (data) {
+
+
+        Data::Struct(DataStruct { fields, .. }) => {
+            let mut field_members = Vec::with_capacity(fields.len());
+            let mut field_names   = Vec::with_capacity(fields.len());
+            let mut field_types   = Vec::with_capacity(fields.len());
+            for (i, Field { ident, ty, .. }) in fields.into_iter().enumerate() {
+                let field_span = ty.span();
+                field_members .push(ident.clone().map_or_else(|| Member::Unnamed(Index::from(i)), Member::Named));
+                field_names   .push(ident.unwrap_or_else(|| format_ident!("field_{}", i)));
+                field_types   .push(quote_spanned!{ field_span => #ty });
+            }
+            let fields_type    = nested_tuple(&field_types);
+            let fields_pattern = nested_tuple(&field_names);
+
+            let read_only_generics = read_only_generics(&generics, &field_types, quote!{ axecs::query::ReadOnlyQuery });
+            let read_only_where    = &read_only_generics.where_clause;
+
+            quote!{
+
+                unsafe impl #impl_generics axecs::query::Query for #ident #ty_generics #where_clause {
+                    type Item = Self;
+                    type State = <#fields_type as axecs::query::Query>::State;
+
+                    fn init_state(world : axecs::query::__Arc<axecs::world::World>, system_id : Option<axecs::system::SystemId>) -> Self::State {
+                        <#fields_type as axecs::query::Query>::init_state(world, system_id)
+                    }
+
+                    unsafe fn acquire(world : axecs::query::__Arc<axecs::world::World>, state : &mut Self::State) -> ::core::task::Poll<axecs::query::QueryAcquireResult<Self::Item>> {
+                        unsafe{ <#fields_type as axecs::query::Query>::acquire(world, state) }
+                            .map(|result| result.map(|#fields_pattern| Self { #( #field_members : #field_names , )* }))
+                    }
+
+                    fn validate() -> axecs::query::QueryValidator {
+                        <#fields_type as axecs::query::Query>::validate()
+                    }
+
+                }
+
+                unsafe impl #impl_generics axecs::query::ReadOnlyQuery for #ident #ty_generics #read_only_where { }
+
+            }
+        },
+
+
+        Data::Enum(DataEnum { enum_token, .. }) => {
+            let span = enum_token.span;
+            quote_spanned!{ span => compile_error!("`Query` can not be derived for enums"); }
+        },
+        Data::Union(DataUnion { union_token, .. }) => {
+            let span = union_token.span;
+            quote_spanned!{ span => compile_error!("`Query` can not be derived for unions"); }
+        }
+
+    }.into()
+}
+
+
+
 #[proc_macro_derive(Resource)]
 pub fn derive_resource(input : TokenStream1) -> TokenStream1 {
     let DeriveInput {
@@ -160,3 +365,31 @@ pub fn derive_label(input : TokenStream1) -> TokenStream1 {
         impl #impl_generics axecs::schedule::label::ScheduleLabel for #ident #ty_generics #where_clause { }
     }.into()
 }
+
+
+
+/// Returns `generics` with a bound requiring each of `field_types` to implement the read-only query trait `bound`.
+fn read_only_generics(generics : &Generics, field_types : &[impl quote::ToTokens], bound : TokenStream) -> Generics {
+    let mut read_only_generics = generics.clone();
+    let     where_clause       = read_only_generics.make_where_clause();
+    for field_type in field_types {
+        // The bounds are made higher-ranked so that they are not checked eagerly,
+        //  which would fail to compile for queries which are not read-only.
+        where_clause.predicates.push(parse_quote!{ for<'__axecs> #field_type : #bound });
+    }
+    read_only_generics
+}
+
+
+/// The largest tuple that the query traits are implemented for.
+const MAX_TUPLE_LEN : usize = 12;
+
+/// Joins the given items into a tuple, nesting tuples as needed to stay within [`MAX_TUPLE_LEN`].
+fn nested_tuple<T : quote::ToTokens>(items : &[T]) -> TokenStream {
+    if (items.len() <= MAX_TUPLE_LEN) {
+        quote!{ ( #( #items , )* ) }
+    } else {
+        let chunks = items.chunks(MAX_TUPLE_LEN).map(nested_tuple).collect::<Vec<_>>();
+        nested_tuple(&chunks)
+    }
+}
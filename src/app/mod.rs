@@ -5,7 +5,9 @@ pub mod plugin;
 use plugin::Plugin;
 
 
-use crate::resource::{ Resource, RawResourceStorage };
+use crate::resource::{ Resource, RawResourceStorage, RawNonSendStorage };
+use crate::world::FromWorld;
+use crate::query::{ Event, EventQueue, EventConfig, EventSender, EventReceiver };
 use crate::schedule::ScheduleStorage;
 use crate::schedule::label::ScheduleLabel;
 use crate::schedule::system::IntoScheduledSystemConfig;
@@ -50,7 +52,10 @@ pub struct App {
     schedules         : Option<ScheduleStorage>,
 
     /// Resources that the [`App`] will start with.
-    resources         : Option<RawResourceStorage>
+    resources         : Option<RawResourceStorage>,
+
+    /// Non-[`Send`] resources that the [`App`] will start with.
+    non_send          : Option<RawNonSendStorage>
 
 }
 
@@ -61,7 +66,8 @@ impl App {
         installed_plugins : BTreeSet::new(),
         runner            : None,
         schedules         : Some(ScheduleStorage::new()),
-        resources         : Some(RawResourceStorage::new())
+        resources         : Some(RawResourceStorage::new()),
+        non_send          : Some(RawNonSendStorage::new())
     } }
 
     /// Installs a [`Plugin`].
@@ -194,9 +200,177 @@ impl App {
         self
     }
 
+    /// Inserts a [`Resource`] keyed by `K` into the application world.
+    ///
+    /// Several [`Resource`]s of the same type can be inserted with different keys.
+    /// They are accessed using [`Res<&R, K>`](crate::resource::Res). [`App::insert_resource`] is the same as using `()` as the key.
+    ///
+    /// ### Panics
+    /// Panics if a [`Resource`] of the same type and key has already been added to this [`App`].
+    #[track_caller]
+    pub fn insert_resource_keyed<R : Resource + 'static, K : 'static>(&mut self, resource : R) -> &mut Self {
+        if (! self.resources.as_mut().expect("App resources have already been taken").insert_keyed::<R, K>(resource)) {
+            panic!("App already has resource {} with key {} inserted", type_name::<R>(), type_name::<K>());
+        }
+        self
+    }
+
+    /// Makes sure a [`Resource`] exists in the application world, constructing it with [`FromWorld`] if needed.
+    ///
+    /// Unlike [`App::insert_resource`], this does not panic if a [`Resource`] of the same type has already been added,
+    ///  and a value inserted with [`App::insert_resource`] is always kept.
+    /// The [`Resource`] is constructed once the world has been created, after every resource has been inserted.
+    /// Only un-keyed [`Resource`]s can be initialised.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    /// # use async_std::main;
+    ///
+    /// #[main]
+    /// async fn main() {
+    ///     let mut app = App::new();
+    ///     app.add_plugin(CycleSchedulerPlugin);
+    ///     app.init_resource::<Score>();
+    ///     app.add_systems(Startup, print_score);
+    ///     # app.add_systems(Startup, async |cmds : Commands| cmds.try_exit(AppExit::Ok));
+    ///     app.run().await;
+    /// }
+    ///
+    /// #[derive(Resource, Default)]
+    /// struct Score {
+    ///     value : u32
+    /// }
+    ///
+    /// async fn print_score(
+    ///     score : Res<&Score>
+    /// ) {
+    ///     println!("{}", score.value);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn init_resource<R : Resource + FromWorld + 'static>(&mut self) -> &mut Self {
+        self.resources.as_mut().expect("App resources have already been taken").init::<R>();
+        self
+    }
+
+    /// Bounds the number of unread events of type `E` that each [`EventReader`](crate::query::EventReader) can hold.
+    ///
+    /// When an event is sent to a reader which is already holding [`EventConfig::capacity`] events, the [`EventPolicy`](crate::query::EventPolicy) decides
+    ///  whether the oldest event is dropped, the new event is dropped, or [`EventWriter::send`](crate::query::EventWriter::send) waits for room.
+    /// Dropped events are counted by the [`DroppedEvents<E>`](crate::query::DroppedEvents) resource, which this inserts.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    /// use axecs::query::{ EventConfig, EventPolicy, DroppedEvents };
+    ///
+    /// #[derive(Event, Clone)]
+    /// struct Packet {
+    ///     data : Vec<u8>
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugin(CycleSchedulerPlugin);
+    /// app.add_event::<Packet>(EventConfig { capacity : 256, policy : EventPolicy::DropOldest });
+    /// app.add_systems(Cycle, report_dropped);
+    ///
+    /// async fn report_dropped(
+    ///     dropped : Res<&DroppedEvents<Packet>>
+    /// ) {
+    ///     println!("{} packets dropped", dropped.count());
+    /// }
+    /// ```
+    ///
+    /// ### Panics
+    /// Panics if events of type `E` have already been added to this [`App`], or if the capacity is zero.
+    #[track_caller]
+    pub fn add_event<E : Event + 'static>(&mut self, config : EventConfig) -> &mut Self {
+        if (config.capacity == 0) {
+            panic!("Event {} can not have a capacity of zero", type_name::<E>());
+        }
+        let queue     = EventQueue::<E>::new_bounded(config);
+        let dropped   = queue.dropped_events();
+        let resources = self.resources.as_mut().expect("App resources have already been taken");
+        if (! resources.insert(queue)) {
+            panic!("App already has event {} added", type_name::<E>());
+        }
+        resources.insert(dropped);
+        self
+    }
+
+    /// Makes events of type `E` buffered.
+    ///
+    /// Instead of each [`EventReader`](crate::query::EventReader) receiving its own copy of every event,
+    ///  events are kept in a shared buffer for `cycles` world cycles after the one they were sent in, and then dropped.
+    /// Each reader keeps track of which events it has read, so every reader sees each event once,
+    ///  including events sent before the reader was created if they are still in the buffer.
+    /// See [`World::cycle`](crate::world::World::cycle).
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Event, Clone)]
+    /// struct Collision {
+    ///     force : f32
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugin(CycleSchedulerPlugin);
+    /// app.add_buffered_event::<Collision>(2);
+    /// app.add_systems(Cycle, play_sounds);
+    ///
+    /// async fn play_sounds(
+    ///     collisions : EventReader<Collision>
+    /// ) {
+    ///     for collision in collisions {
+    ///         println!("Bonk! ({})", collision.force);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ### Panics
+    /// Panics if events of type `E` have already been added to this [`App`].
+    #[track_caller]
+    pub fn add_buffered_event<E : Event + 'static>(&mut self, cycles : u64) -> &mut Self {
+        if (! self.resources.as_mut().expect("App resources have already been taken").insert(EventQueue::<E>::new_buffered(cycles))) {
+            panic!("App already has event {} added", type_name::<E>());
+        }
+        self
+    }
+
+    /// Returns an [`EventSender`] which sends events of type `E` into the world from outside of it, such as from another thread or task.
+    ///
+    /// Events sent before the app is run are kept until the world starts, like events sent by an [`EventWriter`](crate::query::EventWriter).
+    /// See [`EventSender`] for an example.
+    ///
+    /// [`App::add_event`] and [`App::add_buffered_event`] must be called before this, if they are used for `E`.
+    ///
+    /// ### Panics
+    /// Panics if the resources have already been taken from this [`App`].
+    #[track_caller]
+    pub fn event_sender<E : Event + 'static>(&mut self) -> EventSender<E> {
+        EventSender::from_raw(self.resources.as_mut().expect("App resources have already been taken"))
+    }
+
+    /// Returns an [`EventReceiver`] which receives events of type `E` sent in the world, from outside of it, such as from another thread or task.
+    ///
+    /// See [`EventReceiver`] for an example.
+    ///
+    /// [`App::add_event`] and [`App::add_buffered_event`] must be called before this, if they are used for `E`.
+    ///
+    /// ### Panics
+    /// Panics if the resources have already been taken from this [`App`].
+    #[track_caller]
+    pub fn event_receiver<E : Event + 'static>(&mut self) -> EventReceiver<E> {
+        EventReceiver::from_raw(self.resources.as_mut().expect("App resources have already been taken"))
+    }
+
     /// Removes the [`RawResourceStorage`] from this [`App`], returning it.
     ///
     /// This is intended for runner functions and likely should not be used otherwise. See [App::set_runner].
+    /// Runners should call [`World::run_resource_initialisers`](crate::world::World::run_resource_initialisers) once the [`World`](crate::world::World) exists.
     ///
     /// # Panics
     /// Panics if the resources have already been taken from this [`App`].
@@ -205,6 +379,31 @@ impl App {
         mem::replace(&mut self.resources, None).expect("App resources have already been taken")
     }
 
+    /// Inserts a non-[`Send`] resource into the application world.
+    ///
+    /// Systems can access it using [`NonSend`](crate::resource::NonSend).
+    ///
+    /// ### Panics
+    /// Panics if a non-[`Send`] resource of the same type has already been added to this [`App`].
+    #[track_caller]
+    pub fn insert_non_send_resource<R : 'static>(&mut self, resource : R) -> &mut Self {
+        if (! self.non_send.as_mut().expect("App non-send resources have already been taken").insert(resource)) {
+            panic!("App already has non-send resource {} inserted", type_name::<R>());
+        }
+        self
+    }
+
+    /// Removes the [`RawNonSendStorage`] from this [`App`], returning it.
+    ///
+    /// This is intended for runner functions and likely should not be used otherwise. See [App::set_runner].
+    ///
+    /// # Panics
+    /// Panics if the non-send resources have already been taken from this [`App`].
+    #[track_caller]
+    pub fn take_non_send_resources(&mut self) -> RawNonSendStorage {
+        mem::replace(&mut self.non_send, None).expect("App non-send resources have already been taken")
+    }
+
     /// Runs the [`App`].
     ///
     /// This can only be run once. Future attempts to run will panic.
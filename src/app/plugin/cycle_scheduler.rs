@@ -4,7 +4,7 @@
 use crate::app::{ App, AppExit };
 use crate::app::plugin::Plugin;
 use crate::world::World;
-use crate::resource::ResourceStorage;
+use crate::resource::{ ResourceStorage, NonSendStorage };
 use crate::schedule::ScheduleStorage;
 use crate::schedule::label::{ ScheduleLabel, Always, PreStartup, Startup, Cycle, Shutdown, PostShutdown };
 use crate::schedule::system::TypeErasedSystem;
@@ -14,6 +14,7 @@ use core::pin::Pin;
 use core::task::{ Context, Poll };
 use core::mem::{ self, MaybeUninit };
 use core::cell::UnsafeCell;
+use core::sync::atomic::{ AtomicUsize, Ordering };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use alloc::sync::Arc;
@@ -71,7 +72,11 @@ impl CycleSchedulerPlugin {
 
     /// Runs the application using this cycle scheduler.
     async fn run(self, mut app : App) -> AppExit {
-        let world     = Arc::new(World::new_with( ResourceStorage::new_with(app.take_resources()) ));
+        let world = Arc::new(World::new_with_non_send(
+            ResourceStorage::new_with(app.take_resources()),
+            NonSendStorage::new_with(app.take_non_send_resources())
+        ));
+        world.run_resource_initialisers().await;
         let schedules = Arc::new(app.take_schedules());
 
         let scheduler = CycleSchedulerFuture::new(world, schedules);
@@ -98,7 +103,13 @@ struct CycleSchedulerFuture {
     always_futures : Vec<Pin<Box<dyn Future<Output = ()>>>>,
 
     /// The currently running futures.
-    futures        : SparseVec<Pin<Box<dyn Future<Output = ()>>>>
+    futures        : SparseVec<Pin<Box<dyn Future<Output = ()>>>>,
+
+    /// The number of [`Cycle`] systems that are running.
+    cycle_systems  : usize,
+
+    /// The number of [`Cycle`] systems that have finished running at least once during the current world cycle.
+    cycle_finished : Arc<AtomicUsize>
 
 }
 
@@ -135,7 +146,9 @@ impl CycleSchedulerFuture {
             world,
             schedules,
             always_futures : Vec::new(),
-            futures        : SparseVec::new()
+            futures        : SparseVec::new(),
+            cycle_systems  : 0,
+            cycle_finished : Arc::new(AtomicUsize::new(0))
         }
     }
 
@@ -146,7 +159,7 @@ impl CycleSchedulerFuture {
                 let world  = Arc::clone(&self.world);
                 let system = RwLock::arc_clone(&system);
                 Box::pin(async move {
-                    SystemCycleFuture::new(world, system.write().await).await
+                    SystemCycleFuture::new(world, system.write().await, None).await
                 }) as _
             })
             .collect::<Vec<_>>()
@@ -175,17 +188,21 @@ impl CycleSchedulerFuture {
     /// Adds every system under the given label to the running futures.
     ///
     /// These systems will be wrapped in [`SystemCycleFuture`], and will loop until the app begins to exit.
+    ///
+    /// The world cycle is advanced once each of these systems has finished running at least once. See [`World::cycle`].
     fn run_label_cycle<L : ScheduleLabel + 'static>(&mut self, label : L) {
-        self.futures.append(&mut self.schedules.get_schedule(label)
+        let mut systems = self.schedules.get_schedule(label)
             .into_iter().map(|system| {
-                let world  = Arc::clone(&self.world);
-                let system = RwLock::arc_clone(&system);
+                let world    = Arc::clone(&self.world);
+                let system   = RwLock::arc_clone(&system);
+                let finished = Arc::clone(&self.cycle_finished);
                 Box::pin(async move {
-                    SystemCycleFuture::new(Arc::clone(&world), system.write().await).await
+                    SystemCycleFuture::new(Arc::clone(&world), system.write().await, Some(finished)).await
                 }) as _
             })
-            .collect::<Vec<_>>()
-        );
+            .collect::<Vec<_>>();
+        self.cycle_systems += systems.len();
+        self.futures.append(&mut systems);
     }
 
 }
@@ -250,6 +267,10 @@ impl Future for CycleSchedulerFuture {
             },
 
             CycleSchedulerState::Main => {
+                if (self.cycle_finished.load(Ordering::Relaxed) >= self.cycle_systems) {
+                    self.cycle_finished.store(0, Ordering::Relaxed);
+                    self.world.advance_cycle();
+                }
                 if (self.world.is_exiting()) {
                     self.run_label_oneshot(Shutdown);
                     self.state = CycleSchedulerState::Shutdown;
@@ -288,18 +309,28 @@ struct SystemCycleFuture {
     system : UnsafeCell<RwLockWriteGuard<Box<dyn TypeErasedSystem<(), ()>>>>,
 
     /// The currently running future.
-    future : MaybeUninit<Pin<Box<dyn Future<Output = ()>>>>
+    future   : MaybeUninit<Pin<Box<dyn Future<Output = ()>>>>,
+
+    /// The counter to increment the first time the system finishes running during each world cycle, if any.
+    finished : Option<Arc<AtomicUsize>>,
+
+    /// The world cycle that the system last finished running in.
+    counted  : Option<u64>
 
 }
 
 impl SystemCycleFuture {
 
     /// Create a new [`SystemCycleFuture`] from a [`World`] and [`TypeErasedSystem`].
-    fn new(world : Arc<World>, system : RwLockWriteGuard<Box<dyn TypeErasedSystem<(), ()>>>) -> Self {
+    ///
+    /// If `finished` is given, it is incremented the first time the system finishes running during each world cycle.
+    fn new(world : Arc<World>, system : RwLockWriteGuard<Box<dyn TypeErasedSystem<(), ()>>>, finished : Option<Arc<AtomicUsize>>) -> Self {
         let mut cycle = Self {
             world,
-            system : UnsafeCell::new(system),
-            future : MaybeUninit::uninit()
+            system   : UnsafeCell::new(system),
+            future   : MaybeUninit::uninit(),
+            finished,
+            counted  : None
         };
         cycle.future.write(Box::pin(Self::cycle(
             Arc::clone(&cycle.world),
@@ -328,6 +359,13 @@ impl Future for SystemCycleFuture {
         if let Poll::Ready(_) = unsafe{ self.future.assume_init_mut() }.as_mut().poll(ctx) {
             ctx.waker().wake_by_ref();
             let world = Arc::clone(&self.world);
+            if let Some(finished) = &self.finished {
+                let cycle = world.cycle();
+                if (self.counted != Some(cycle)) {
+                    finished.fetch_add(1, Ordering::Relaxed);
+                    self.counted = Some(cycle);
+                }
+            }
             if (world.is_exiting()) { return Poll::Ready(()); }
             // SAFETY: `self.future` is always initialised.
             //         It will be re-written immediately below.
@@ -7,6 +7,11 @@ pub use cycle_scheduler::CycleSchedulerPlugin;
 mod ctrlc;
 pub use ctrlc::{ CtrlCPlugin, CtrlCStatus };
 
+#[cfg(all(feature = "serde", not(feature = "no_std")))]
+mod record;
+#[cfg(all(feature = "serde", not(feature = "no_std")))]
+pub use record::{ EventRecorderPlugin, EventReplayPlugin };
+
 
 use crate::app::App;
 
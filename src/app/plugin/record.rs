@@ -0,0 +1,271 @@
+//! Recording events to a file, and replaying them later.
+
+
+use crate::app::{ App, Plugin };
+use crate::query::{ Event, EventReader, EventWriter, Local };
+use crate::schedule::label::Cycle;
+use core::any::type_name;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+use std::fs::{ self, File };
+use std::io::{ LineWriter, Write };
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{ self, Value };
+
+
+/// The file that an [`EventRecorderPlugin`] writes to.
+type RecordingFile = Arc<Mutex<LineWriter<File>>>;
+
+/// An event read from a recording, as the world cycle it was sent in, its tag, and its data.
+type RecordedEvent = (u64, String, Value);
+
+
+/// Records every event of some selected types to a file, along with the world cycle that each was sent in.
+///
+/// The file contains one JSON object per line, in the form `{"cycle":3,"data":...,"event":"damage"}`.
+/// The `event` field is a tag identifying the event type. See [`EventRecorderPlugin::record_as`].
+/// Events are read by a [`Cycle`] system for each type, so events sent after the last [`Cycle`] has run are not recorded.
+/// The recording can be played back using [`EventReplayPlugin`].
+///
+/// ### Examples
+/// ```rust,no_run
+/// use axecs::prelude::*;
+/// use axecs::app::plugin::EventRecorderPlugin;
+/// use serde::Serialize;
+///
+/// #[derive(Event, Clone, Serialize)]
+/// struct Input {
+///     key : char
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugin(CycleSchedulerPlugin);
+/// app.add_plugin(EventRecorderPlugin::new("session.jsonl").record_as::<Input>("input"));
+/// ```
+///
+/// ### Panics
+/// Panics when added to an [`App`] if the file can not be created.
+#[doc(cfg(feature = "serde"))]
+pub struct EventRecorderPlugin {
+
+    /// The path of the file to record to.
+    path   : PathBuf,
+
+    /// Functions which add a recording system for each selected event type.
+    events : Vec<Box<dyn FnOnce(&mut App, RecordingFile) -> ()>>
+
+}
+
+impl EventRecorderPlugin {
+
+    /// Creates an [`EventRecorderPlugin`] which records to the file at `path`, replacing it if it exists.
+    ///
+    /// No events are recorded until their types are selected with [`EventRecorderPlugin::record`].
+    pub fn new(path : impl Into<PathBuf>) -> Self { Self {
+        path   : path.into(),
+        events : Vec::new()
+    } }
+
+    /// Selects events of type `E` to be recorded, tagged with the [`type_name`] of `E`.
+    ///
+    /// The [`type_name`] of a type is not guaranteed to be stable between compiler versions, or when the type is moved.
+    /// Use [`EventRecorderPlugin::record_as`] for recordings which need to be played back by other builds.
+    pub fn record<E : Event + Serialize + 'static>(self) -> Self {
+        self.record_as::<E>(type_name::<E>())
+    }
+
+    /// Selects events of type `E` to be recorded, tagged with `tag`.
+    ///
+    /// The same tag must be given to [`EventReplayPlugin::replay_as`] to play them back.
+    pub fn record_as<E : Event + Serialize + 'static>(mut self, tag : &'static str) -> Self {
+        self.events.push(Box::new(move |app, file| add_recorder::<E>(app, file, tag)));
+        self
+    }
+
+}
+
+impl Plugin for EventRecorderPlugin {
+    fn build(self, app : &mut App) {
+        let file = File::create(&self.path).unwrap_or_else(|err| panic!("Failed to create event recording {} ({})", self.path.display(), err));
+        let file = Arc::new(Mutex::new(LineWriter::new(file)));
+        for add_recorder in self.events {
+            add_recorder(app, Arc::clone(&file));
+        }
+    }
+}
+
+/// Adds a system which writes every event of type `E` to `file`, tagged with `tag`.
+fn add_recorder<E : Event + Serialize + 'static>(app : &mut App, file : RecordingFile, tag : &'static str) {
+    app.add_systems(Cycle, async move |events : EventReader<E>| {
+        let mut file = file.lock().unwrap_or_else(|err| err.into_inner());
+        while let Ok((cycle, event)) = events.try_read_with_cycle() {
+            let line = serde_json::json!({ "cycle" : cycle, "event" : tag, "data" : event });
+            writeln!(file, "{}", line).expect("Failed to write to event recording");
+        }
+    });
+}
+
+
+/// Plays back events recorded by an [`EventRecorderPlugin`].
+///
+/// Each recorded event of the selected types is sent through an [`EventWriter`] by a [`Cycle`] system,
+///  in the same world cycle that it was originally sent in. See [`World::cycle`](crate::world::World::cycle).
+/// Recorded events with other tags are ignored.
+///
+/// The replaying systems are not ordered before other [`Cycle`] systems, as [`Cycle`] systems run alongside each other.
+/// An [`EventReader`] which runs before the replaying system in a cycle receives that cycle's events the next time it runs,
+///  so systems should not rely on receiving replayed events in exactly the same run that they were originally received in.
+///
+/// ### Examples
+/// ```rust,no_run
+/// use axecs::prelude::*;
+/// use axecs::app::plugin::EventReplayPlugin;
+/// use serde::Deserialize;
+///
+/// #[derive(Event, Clone, Deserialize)]
+/// struct Input {
+///     key : char
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugin(CycleSchedulerPlugin);
+/// app.add_plugin(EventReplayPlugin::new("session.jsonl").replay_as::<Input>("input"));
+/// ```
+///
+/// ### Panics
+/// Panics when added to an [`App`] if the file can not be read, or if it contains an event which can not be parsed.
+#[doc(cfg(feature = "serde"))]
+pub struct EventReplayPlugin {
+
+    /// The path of the file to play back.
+    path   : PathBuf,
+
+    /// Functions which add a replaying system for each selected event type.
+    events : Vec<Box<dyn FnOnce(&mut App, &[RecordedEvent]) -> ()>>
+
+}
+
+impl EventReplayPlugin {
+
+    /// Creates an [`EventReplayPlugin`] which plays back the file at `path`.
+    ///
+    /// No events are played back until their types are selected with [`EventReplayPlugin::replay`].
+    pub fn new(path : impl Into<PathBuf>) -> Self { Self {
+        path   : path.into(),
+        events : Vec::new()
+    } }
+
+    /// Selects events of type `E` to be played back, from events tagged with the [`type_name`] of `E`. See [`EventRecorderPlugin::record`].
+    pub fn replay<E : Event + DeserializeOwned + Send + Sync + 'static>(self) -> Self {
+        self.replay_as::<E>(type_name::<E>())
+    }
+
+    /// Selects events of type `E` to be played back, from events tagged with `tag`. See [`EventRecorderPlugin::record_as`].
+    pub fn replay_as<E : Event + DeserializeOwned + Send + Sync + 'static>(mut self, tag : &'static str) -> Self {
+        self.events.push(Box::new(move |app, recorded| add_replayer::<E>(app, recorded, tag)));
+        self
+    }
+
+}
+
+impl Plugin for EventReplayPlugin {
+    fn build(self, app : &mut App) {
+        let recording = fs::read_to_string(&self.path).unwrap_or_else(|err| panic!("Failed to read event recording {} ({})", self.path.display(), err));
+        let recorded  = recording.lines().enumerate().filter(|(_, line)| ! line.trim().is_empty()).map(|(i, line)| {
+            let mut line = serde_json::from_str::<Value>(line).ok()
+                .filter(|line| line["cycle"].is_u64() && line["event"].is_string())
+                .unwrap_or_else(|| panic!("Failed to parse event recording {} line {}", self.path.display(), i + 1));
+            (line["cycle"].as_u64().unwrap(), line["event"].as_str().unwrap().into(), line["data"].take())
+        }).collect::<Vec<RecordedEvent>>();
+        for add_replayer in self.events {
+            add_replayer(app, &recorded);
+        }
+    }
+}
+
+/// Adds a system which sends each recorded event tagged with `tag` as an `E`, in the cycle it was recorded in.
+fn add_replayer<E : Event + DeserializeOwned + Send + Sync + 'static>(app : &mut App, recorded : &[RecordedEvent], tag : &'static str) {
+    let events = recorded.iter()
+        .filter(|(_, event, _)| event == tag)
+        .map(|(cycle, _, data)| (*cycle, serde_json::from_value::<E>(data.clone())
+            .unwrap_or_else(|err| panic!("Failed to parse recorded event {} ({})", tag, err))
+        ))
+        .collect::<Box<[_]>>();
+    app.add_systems(Cycle, async move |writer : EventWriter<E>, mut next : Local<usize>| {
+        while let Some((cycle, event)) = events.get(*next) && (*cycle <= writer.cycle()) {
+            writer.send(event.clone()).await;
+            *next += 1;
+        }
+    });
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use serde::Deserialize;
+    use async_std::task::block_on;
+
+    #[derive(Event, Clone, Serialize, Deserialize, PartialEq, Debug)]
+    struct Input {
+        key : u64
+    }
+
+    #[derive(Event, Clone, Serialize)]
+    struct Ignored;
+
+    /// Adds a system which exits the app once the world reaches `cycle`.
+    fn exit_at(app : &mut App, cycle : u64) {
+        app.add_systems(Cycle, async move |cmds : Commands, writer : EventWriter<Ignored>| {
+            if (writer.cycle() >= cycle) { cmds.try_exit(AppExit::Ok); }
+        });
+    }
+
+    #[test]
+    fn recordings_replay_at_the_same_cycles() { block_on(async {
+        let path = std::env::temp_dir().join(format!("axecs-recording-{}.jsonl", std::process::id()));
+
+        let mut app = App::new();
+        app.add_plugin(CycleSchedulerPlugin);
+        app.add_plugin(EventRecorderPlugin::new(&path).record_as::<Input>("input"));
+        app.add_systems(Cycle, async |writer : EventWriter<Input>, ignored : EventWriter<Ignored>, mut last : Local<Option<u64>>| {
+            let cycle = writer.cycle();
+            if ((1..=3).contains(&cycle) && *last != Some(cycle)) {
+                writer.send(Input { key : cycle * 10 }).await;
+                ignored.send(Ignored).await;
+            }
+            *last = Some(cycle);
+        });
+        exit_at(&mut app, 6);
+        app.run().await;
+
+        let recorded = fs::read_to_string(&path).unwrap();
+        assert_eq!(recorded.lines().count(), 3);
+        assert!(recorded.lines().all(|line| line.contains(r#""event":"input""#)));
+
+        let replayed = Arc::new(Mutex::new(Vec::new()));
+        let mut app = App::new();
+        app.add_plugin(CycleSchedulerPlugin);
+        app.add_plugin(EventReplayPlugin::new(&path).replay_as::<Input>("input"));
+        app.add_systems(Cycle, { let replayed = Arc::clone(&replayed); async move |reader : EventReader<Input>| {
+            while let Ok(event) = reader.try_read_with_cycle() {
+                replayed.lock().unwrap().push(event);
+            }
+        } });
+        exit_at(&mut app, 6);
+        app.run().await;
+        fs::remove_file(&path).unwrap();
+
+        let replayed = replayed.lock().unwrap();
+        assert_eq!(replayed.iter().map(|(_, event)| event.key).collect::<Vec<_>>(), [10, 20, 30]);
+        // Each event is sent in exactly the cycle it was recorded in.
+        assert!(replayed.iter().all(|(cycle, event)| *cycle == event.key / 10));
+    }); }
+
+}
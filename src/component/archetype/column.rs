@@ -4,19 +4,29 @@
 use crate::component::{ Component, ComponentTypeInfo };
 use core::any::TypeId;
 use core::alloc::Layout;
-use core::ptr::NonNull;
-use alloc::alloc::{ alloc, dealloc, handle_alloc_error };
-use alloc::vec::Vec;
+use core::ops::Range;
+use core::ptr::{ self, NonNull };
+use alloc::alloc::{ alloc, realloc, dealloc, handle_alloc_error };
 
 
 /// A single column of an [`Archetype`](crate::component::archetype::Archetype), storing a single [`Component`] type.
+///
+/// The cells of a column are stored contiguously in a single allocation, so that occupied rows can be accessed as slices.
 pub struct ArchetypeColumn {
 
     /// The [`ComponentTypeInfo`] of the [`Component`] type stored in this column.
     type_info : ComponentTypeInfo,
 
-    /// The allocated cells in this column.
-    cells     : Vec<ArchetypeCell>
+    /// A pointer to the start of the allocated cells.
+    ///
+    /// If no memory has been allocated, or the [`Component`] type is zero-sized, this is dangling but well-aligned.
+    data_ptr  : NonNull<u8>,
+
+    /// The number of cells in this column, occupied or not.
+    len       : usize,
+
+    /// The number of cells that memory has been allocated for.
+    capacity  : usize
 
 }
 
@@ -24,13 +34,24 @@ impl ArchetypeColumn {
 
     /// Creates a new column with the given [`ComponentTypeInfo`].
     ///
-    /// # Safety:
+    /// # Safety
     /// [`ArchetypeColumn`] does not properly clean itself up on drop.
     /// [`ArchetypeColumn::drop_dealloc_except`] must be called to properly deallocate.
-    pub unsafe fn new(type_info : ComponentTypeInfo) -> Self { Self {
-        type_info,
-        cells     : Vec::new()
-    } }
+    pub unsafe fn new(type_info : ComponentTypeInfo) -> Self {
+        let layout = type_info.layout();
+        Self {
+            type_info,
+            // SAFETY: Alignments are never zero.
+            data_ptr  : unsafe{ NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) },
+            len       : 0,
+            capacity  : if (layout.size() == 0) { usize::MAX } else { 0 }
+        }
+    }
+
+    /// The [`ComponentTypeInfo`] of the [`Component`] type stored in this column.
+    pub fn type_info(&self) -> ComponentTypeInfo {
+        self.type_info
+    }
 
     /// The [`TypeId`] of the [`Component`] type stored in this column.
     pub fn type_id(&self) -> TypeId {
@@ -54,6 +75,23 @@ impl ArchetypeColumn {
         self.type_info.name()
     }
 
+    /// Returns a pointer to a cell by `index`, without checking that it is in bounds.
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that `index` is no greater than the number of allocated cells.
+    unsafe fn cell_ptr(&self, index : usize) -> NonNull<u8> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        unsafe{ self.data_ptr.add(index * self.type_layout().size()) }
+    }
+
+    /// Returns the [`Layout`] of an allocation holding `capacity` cells.
+    fn array_layout(&self, capacity : usize) -> Layout {
+        let layout = self.type_layout();
+        layout.size().checked_mul(capacity)
+            .and_then(|size| Layout::from_size_align(size, layout.align()).ok())
+            .expect("capacity overflow")
+    }
+
     /// Returns a reference to the value in a cell by `index`.
     ///
     /// # Safety
@@ -62,7 +100,7 @@ impl ArchetypeColumn {
     /// - `C` is the type stored in this column.
     pub unsafe fn get_ref<C : Component>(&self, index : usize) -> &C {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked(index).get_ref::<C>() }
+        unsafe{ self.cell_ptr(index).cast::<C>().as_ref() }
     }
 
     /// Returns a mutable reference to the value in a cell by `index`.
@@ -73,7 +111,7 @@ impl ArchetypeColumn {
     /// - `C` is the type stored in this column.
     pub unsafe fn get_mut<C : Component>(&mut self, index : usize) -> &mut C {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked_mut(index).get_mut::<C>() }
+        unsafe{ self.cell_ptr(index).cast::<C>().as_mut() }
     }
 
     /// Returns a pointer to the value in a cell by `index`.
@@ -82,11 +120,46 @@ impl ArchetypeColumn {
     /// The caller is responsible for ensuring that
     /// - the cell at the given `index` **is occupied**.
     /// - `C` is the type stored in this column.
-    /// - the pointer is not used when the cell is unoccupied, or this column is dropped.
+    /// - the pointer is not used when the cell is unoccupied, a cell is pushed onto this column, or this column is dropped.
     /// - data-races are prevented.
     pub unsafe fn get_ptr<C : Component>(&self, index : usize) -> *mut C {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked(index).get_ptr::<C>() }
+        unsafe{ self.cell_ptr(index) }.cast::<C>().as_ptr()
+    }
+
+    /// Returns a slice of the values in a range of cells.
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that
+    /// - every cell in the given `range` **is occupied**.
+    /// - `C` is the type stored in this column.
+    pub unsafe fn get_slice_ref<C : Component>(&self, range : Range<usize>) -> &[C] {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        unsafe{ &*self.get_slice_ptr(range) }
+    }
+
+    /// Returns a mutable slice of the values in a range of cells.
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that
+    /// - every cell in the given `range` **is occupied**.
+    /// - `C` is the type stored in this column.
+    pub unsafe fn get_slice_mut<C : Component>(&mut self, range : Range<usize>) -> &mut [C] {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        unsafe{ &mut*self.get_slice_ptr(range) }
+    }
+
+    /// Returns a pointer to a slice of the values in a range of cells.
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that
+    /// - every cell in the given `range` **is occupied**.
+    /// - `C` is the type stored in this column.
+    /// - the pointer is not used when any of the cells are unoccupied, a cell is pushed onto this column, or this column is dropped.
+    /// - data-races are prevented.
+    pub unsafe fn get_slice_ptr<C : Component>(&self, range : Range<usize>) -> *mut [C] {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        ptr::slice_from_raw_parts_mut(unsafe{ self.cell_ptr(range.start) }.cast::<C>().as_ptr(), range.len())
     }
 
     /// Pushes a new cell onto this column.
@@ -96,11 +169,31 @@ impl ArchetypeColumn {
     /// # Safety
     /// The caller is responsible for ensuring that `C` is the type stored in this column.
     pub unsafe fn push<C : Component>(&mut self, component : C) {
+        if (self.len == self.capacity) {
+            self.grow();
+        }
+        let index = self.len;
+        self.len += 1;
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        // SAFETY: The caller is responsible for properly disposing of this column and its cells.
-        self.cells.push(unsafe{ ArchetypeCell::new(component) });
+        //         The cell at `index` was allocated above, and has not been occupied yet.
+        unsafe{ self.write(index, component); }
     }
 
+    /// Doubles the capacity of this column, or allocates it for the first time.
+    fn grow(&mut self) {
+        let new_capacity = if (self.capacity == 0) { 4 } else { self.capacity.checked_mul(2).expect("capacity overflow") };
+        let new_layout   = self.array_layout(new_capacity);
+        let data_ptr     = if (self.capacity == 0) {
+            // SAFETY: The column is not zero-sized, as zero-sized columns never need to grow.
+            unsafe{ alloc(new_layout) }
+        } else {
+            // SAFETY: `self.data_ptr` was allocated with the layout of `self.capacity` cells.
+            unsafe{ realloc(self.data_ptr.as_ptr(), self.array_layout(self.capacity), new_layout.size()) }
+        };
+        let Some(data_ptr) = NonNull::new(data_ptr) else { handle_alloc_error(new_layout) };
+        self.data_ptr = data_ptr;
+        self.capacity = new_capacity;
+    }
 
     /// Replaces a cell on this column by `index`, without dropping the previous value.
     ///
@@ -112,7 +205,7 @@ impl ArchetypeColumn {
     /// - `C` is the type stored in this column.
     pub unsafe fn write<C : Component>(&mut self, index : usize, component : C) {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked_mut(index).write(component) }
+        unsafe{ self.cell_ptr(index).cast::<C>().write(component); }
     }
 
     /// Drops the value stored in a cell by `index`.
@@ -124,7 +217,7 @@ impl ArchetypeColumn {
     pub unsafe fn drop(&mut self, index : usize) {
         let drop = self.type_drop();
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked_mut(index).drop(drop); }
+        unsafe{ drop(self.cell_ptr(index)); }
     }
 
     /// Reads the value stored in a cell by `index`, without modifying the memory.
@@ -137,7 +230,7 @@ impl ArchetypeColumn {
     /// - `C` is the type stored in this column.
     pub unsafe fn read<C : Component>(&self, index : usize) -> C {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
-        unsafe{ self.cells.get_unchecked(index).read::<C>() }
+        unsafe{ self.cell_ptr(index).cast::<C>().read() }
     }
 
     /// Drops all cells except the given indices, and deallocates all cells' memory.
@@ -148,15 +241,15 @@ impl ArchetypeColumn {
     /// The caller is responsible for ensuring that:
     /// - `except_indices` contains the index for **every cell that is unoccupied**. No more, no less.
     pub unsafe fn drop_dealloc_except(&mut self, except_indices : &[usize]) {
-        let layout = self.type_layout();
-        let drop   = self.type_drop();
-        for (i, cell) in self.cells.iter_mut().enumerate() {
+        for i in 0..self.len {
             if (! except_indices.contains(&i)) {
                 // SAFETY: The caller is responsible for upholding the safety guarantees.
-                unsafe{ cell.drop(drop); }
+                unsafe{ self.drop(i); }
             }
-            // SAFETY: The caller is responsible for upholding the safety guarantees.
-            unsafe{ cell.dealloc(layout); }
+        }
+        if (self.type_layout().size() > 0 && self.capacity > 0) {
+            // SAFETY: `self.data_ptr` was allocated with the layout of `self.capacity` cells.
+            unsafe{ dealloc(self.data_ptr.as_ptr(), self.array_layout(self.capacity)); }
         }
     }
 
@@ -164,6 +257,9 @@ impl ArchetypeColumn {
 
 
 /// A single cell in an [`Archetype`](crate::component::archetype::Archetype).
+///
+/// Archetypes no longer store each cell in its own allocation, so this type is unused by the crate.
+#[deprecated(since = "0.1.0", note = "archetype cells are now stored contiguously in an `ArchetypeColumn`")]
 pub struct ArchetypeCell {
 
     /// A pointer to the contained value.
@@ -171,13 +267,14 @@ pub struct ArchetypeCell {
 
 }
 
+#[expect(deprecated)]
 impl ArchetypeCell {
 
     /// Creates a new cell with the given [`Component`] type.
     ///
     /// Consider the new cell **occupied**.
     ///
-    /// # Safety:
+    /// # Safety
     /// [`ArchetypeCell`] does not properly clean itself up on drop.
     /// [`ArchetypeCell::drop`] and [`ArchetypeCell::dealloc`] must be called to properly deallocate.
     pub unsafe fn new<C : Component>(component : C) -> Self {
@@ -279,3 +376,118 @@ impl ArchetypeCell {
     }
 
 }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use core::sync::atomic::{ AtomicUsize, Ordering };
+
+    /// A component which counts how many times it has been dropped.
+    struct Tracked {
+        value : usize,
+        drops : Arc<AtomicUsize>
+    }
+    impl Component for Tracked { }
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.drops.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    struct Value(u64);
+    impl Component for Value { }
+
+    /// A zero-sized component which counts how many times it has been dropped.
+    struct Marker;
+    impl Component for Marker { }
+    static MARKER_DROPS : AtomicUsize = AtomicUsize::new(0);
+    impl Drop for Marker {
+        fn drop(&mut self) {
+            MARKER_DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn growth_keeps_values() {
+        let drops      = Arc::new(AtomicUsize::new(0));
+        // SAFETY: The column is cleaned up with `drop_dealloc_except` below.
+        let mut column = unsafe{ ArchetypeColumn::new(ComponentTypeInfo::of::<Tracked>()) };
+        for value in 0..100 {
+            // SAFETY: `Tracked` is the type stored in this column.
+            unsafe{ column.push(Tracked { value, drops : Arc::clone(&drops) }); }
+        }
+        assert_eq!(column.len, 100);
+        assert!(column.capacity >= 100);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        for value in 0..100 {
+            // SAFETY: Every cell is occupied by a `Tracked`.
+            assert_eq!(unsafe{ column.get_ref::<Tracked>(value) }.value, value);
+        }
+
+        // SAFETY: Cells 10 and 20 are occupied, and are considered unoccupied afterwards.
+        unsafe{ column.drop(10); }
+        let read = unsafe{ column.read::<Tracked>(20) };
+        assert_eq!(read.value, 20);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+        drop(read);
+        assert_eq!(drops.load(Ordering::SeqCst), 2);
+
+        // SAFETY: Cell 10 is unoccupied.
+        unsafe{ column.write(10, Tracked { value : 110, drops : Arc::clone(&drops) }); }
+        assert_eq!(unsafe{ column.get_ref::<Tracked>(10) }.value, 110);
+
+        // SAFETY: Cell 20 is the only unoccupied cell.
+        unsafe{ column.drop_dealloc_except(&[20]); }
+        assert_eq!(drops.load(Ordering::SeqCst), 101);
+        assert_eq!(Arc::strong_count(&drops), 1);
+    }
+
+    #[test]
+    fn zero_sized_columns_never_allocate() {
+        // SAFETY: The column is cleaned up with `drop_dealloc_except` below.
+        let mut column = unsafe{ ArchetypeColumn::new(ComponentTypeInfo::of::<Marker>()) };
+        assert_eq!(column.capacity, usize::MAX);
+        let data_ptr = column.data_ptr;
+        for _ in 0..1000 {
+            // SAFETY: `Marker` is the type stored in this column.
+            unsafe{ column.push(Marker); }
+        }
+        assert_eq!(column.len, 1000);
+        assert_eq!(column.capacity, usize::MAX);
+        assert_eq!(column.data_ptr, data_ptr);
+        // SAFETY: Every cell in the range is occupied by a `Marker`.
+        assert_eq!(unsafe{ column.get_slice_ref::<Marker>(0..1000) }.len(), 1000);
+
+        let before = MARKER_DROPS.load(Ordering::SeqCst);
+        // SAFETY: Cells 0, 1, and 2 are treated as unoccupied.
+        unsafe{ column.drop_dealloc_except(&[0, 1, 2]); }
+        assert_eq!(MARKER_DROPS.load(Ordering::SeqCst) - before, 997);
+    }
+
+    #[test]
+    fn slices_cover_the_requested_range() {
+        // SAFETY: The column is cleaned up with `drop_dealloc_except` below.
+        let mut column = unsafe{ ArchetypeColumn::new(ComponentTypeInfo::of::<Value>()) };
+        for value in 0..10 {
+            // SAFETY: `Value` is the type stored in this column.
+            unsafe{ column.push(Value(value)); }
+        }
+        // SAFETY: Every cell in the range is occupied by a `Value`.
+        for value in unsafe{ column.get_slice_mut::<Value>(3..7) } {
+            value.0 *= 10;
+        }
+        // SAFETY: Every cell in the range is occupied by a `Value`.
+        let values = unsafe{ column.get_slice_ref::<Value>(2..8) }.to_vec();
+        assert_eq!(values, Vec::from([2, 30, 40, 50, 60, 7].map(Value)));
+        // SAFETY: Every cell in the range is occupied by a `Value`.
+        assert!(unsafe{ column.get_slice_ref::<Value>(5..5) }.is_empty());
+        // SAFETY: Every cell is occupied.
+        unsafe{ column.drop_dealloc_except(&[]); }
+    }
+
+}
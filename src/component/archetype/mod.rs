@@ -27,7 +27,7 @@ mod column;
 pub use column::*;
 
 
-use crate::component::Component;
+use crate::component::{ Component, ComponentTypeInfo };
 use crate::component::bundle::ComponentBundle;
 use crate::component::query::{ ComponentQuery, ReadOnlyComponentQuery };
 #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
@@ -35,6 +35,7 @@ use crate::util::unqualified::UnqualifiedTypeName;
 use core::any::TypeId;
 use core::fmt;
 use core::cell::UnsafeCell;
+use core::ops::Range;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 
@@ -108,9 +109,15 @@ pub struct Archetype {
     /// The number of allocated rows.
     rows_dense_next : usize,
 
+    /// The number of occupied rows.
+    rows_occupied   : usize,
+
     /// Rows that are allocated, but unoccupied.
     /// A newly spawned entity can occupy these rows instead of allocating more memory.
-    unoccupied_rows : Vec<usize>
+    unoccupied_rows : Vec<usize>,
+
+    /// Occupied rows whose entities are being despawned, while their [`Component::ON_REMOVE`] hooks run.
+    despawning_rows : Vec<usize>
 
 }
 
@@ -150,7 +157,9 @@ impl Archetype {
         //         in `self.free_rows` as the rows to skip dropping.
         columns         : C::type_info().into_iter().map(|cti| UnsafeCell::new(unsafe{ ArchetypeColumn::new(cti) })).collect::<Box<[_]>>(),
         rows_dense_next : 0,
+        rows_occupied   : 0,
         unoccupied_rows : Vec::new(),
+        despawning_rows : Vec::new()
     } }
 
     /// Creates a new archetype from a [`ComponentBundle`].
@@ -216,6 +225,31 @@ impl Archetype {
         Some(self.rows().map(|row| unsafe{ &mut*column.get_ptr(row) }))
     }
 
+    /// Returns an [`Iterator`] over contiguous slices of the cells in a column from this archetype.
+    ///
+    /// Each slice covers one range of consecutive populated rows. See [`Archetype::row_ranges`].
+    ///
+    /// If this archetype does not contain a column of type `C`, `None` is returned.
+    pub fn get_column_chunks_ref<C : Component + 'static>(&self) -> Option<impl Iterator<Item = &[C]>> {
+        let column = self.get_column_ref::<C>()?;
+        // SAFETY: `self` is borrowed immutably, preventing it from being accessed mutably throughout
+        //         the lifetime of the returned value. `row_ranges` only returns populated rows.
+        Some(self.row_ranges().map(|rows| unsafe{ column.get_slice_ref(rows) }))
+    }
+
+    /// Returns an [`Iterator`] over contiguous mutable slices of the cells in a column from this archetype.
+    ///
+    /// Each slice covers one range of consecutive populated rows. See [`Archetype::row_ranges`].
+    ///
+    /// If this archetype does not contain a column of type `C`, `None` is returned.
+    pub fn get_column_chunks_mut<C : Component + 'static>(&mut self) -> Option<impl Iterator<Item = &mut [C]>> {
+        let column = self.get_column_ref::<C>()?;
+        // SAFETY: `self` is borrowed mutably, preventing it from being accessed throughout the
+        //         lifetime of the returned value. `row_ranges` only returns populated rows, and
+        //         the ranges never overlap.
+        Some(self.row_ranges().map(|rows| unsafe{ &mut*column.get_slice_ptr(rows) }))
+    }
+
     /// Returns a pointer to a column from this archetype.
     ///
     /// If this archetype does not contain a column of type `C`, `None` is returned.
@@ -247,11 +281,54 @@ impl Archetype {
         Some(self.rows().map(|row| unsafe{ column.get_ptr(row) }))
     }
 
+    /// Returns an [`Iterator`] over the [`ComponentTypeInfo`]s of the columns in this archetype.
+    pub fn type_infos(&self) -> impl Iterator<Item = ComponentTypeInfo> + '_ {
+        self.columns.iter().map(|column| {
+            // SAFETY: Only the type info is read, which is never modified after the column is created.
+            unsafe{ &*column.get() }.type_info()
+        })
+    }
+
     /// Returns `true` if the archetype has a given `row` populated.
     pub fn has_row(&self, row : usize) -> bool {
         (row < self.rows_dense_next) && (! self.unoccupied_rows.contains(&row))
     }
 
+    /// Returns `true` if the given `row` is populated, and its entity is being despawned by [`World::despawn`](crate::world::World::despawn).
+    pub fn is_despawning(&self, row : usize) -> bool {
+        self.despawning_rows.contains(&row)
+    }
+
+    /// Marks a populated `row` as being despawned, until it is removed by [`Archetype::despawn_unchecked`].
+    ///
+    /// Returns `false` if the row is not populated, or is already being despawned.
+    pub(crate) fn begin_despawn(&mut self, row : usize) -> bool {
+        if (! self.has_row(row) || self.is_despawning(row)) { return false; }
+        self.despawning_rows.push(row);
+        true
+    }
+
+    /// Returns the number of populated rows in this archetype.
+    pub fn row_count(&self) -> usize {
+        self.rows_occupied
+    }
+
+    /// Returns an [`Iterator`] over the ranges of consecutive populated rows in this archetype.
+    ///
+    /// Rows left unoccupied by [`Archetype::despawn_unchecked`] split the ranges, until they are reused.
+    pub fn row_ranges(&self) -> impl Iterator<Item = Range<usize>> + use<> {
+        let mut unoccupied_rows = self.unoccupied_rows.clone();
+        unoccupied_rows.sort_unstable();
+        let mut ranges = Vec::with_capacity(unoccupied_rows.len() + 1);
+        let mut start  = 0;
+        for row in unoccupied_rows {
+            if (row > start) { ranges.push(start..row); }
+            start = row + 1;
+        }
+        if (start < self.rows_dense_next) { ranges.push(start..self.rows_dense_next); }
+        ranges.into_iter()
+    }
+
     /// Returns an [`Iterator`] over the populated rows in this archetype.
     pub fn rows<'l>(&'l self) -> impl Iterator<Item = usize> + 'l {
         (0..self.rows_dense_next).filter(|row| ! self.unoccupied_rows.contains(row))
@@ -284,6 +361,7 @@ impl Archetype {
             unsafe{ bundle.push_into(self); }
             row
         };
+        self.rows_occupied += 1;
         row
     }
 
@@ -298,7 +376,9 @@ impl Archetype {
             // SAFETY: The caller is responsible for ensuring that the given `row` is currently occupied.
             unsafe{ column.get_mut().drop(row); }
         }
+        self.despawning_rows.retain(|&despawning| despawning != row);
         self.unoccupied_rows.push(row);
+        self.rows_occupied -= 1;
     }
 
     /// Returns an [`Iterator`] over the requested columns in this archetype, without checking if the given [`ReadOnlyComponentQuery`] is valid.
@@ -338,7 +418,7 @@ impl fmt::Debug for Archetype {
             #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
             write!(f, "w{}a{},", column.type_layout().size(), column.type_layout().align())?;
         }
-        write!(f, ")>[_; {}]", self.rows_dense_next - self.unoccupied_rows.len())?;
+        write!(f, ")>[_; {}]", self.rows_occupied)?;
         Ok(())
     }
 }
@@ -404,6 +484,7 @@ mod tests {
 
         // Despawn entity.
         unsafe{ archetype.despawn_unchecked(entity0_row) };
+        assert_eq!(archetype.row_count(), 1);
 
         // Reuse open rows.
         let entity2_row = unsafe{ archetype.spawn_unchecked::<Bundle>((
@@ -411,6 +492,7 @@ mod tests {
             ComponentTwo { value : 161718 }
         )) };
         assert_eq!(entity2_row, 0);
+        assert_eq!(archetype.row_count(), 2);
 
         // Columns don't exist.
         let Some(_) = archetype.get_column_cells_mut::<ComponentOne>() else { panic!("Column for ComponentOne should exist, but it does not.") };
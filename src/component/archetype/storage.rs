@@ -1,7 +1,9 @@
 //! A wrapper around many [`Archetype`]s with a safe API for operating on them.
 
 
+use crate::world::{ World, Commands };
 use crate::entity::{ Entity, Entities };
+use crate::component::{ ComponentTypeInfo, ComponentHook };
 use crate::component::bundle::ComponentBundle;
 use crate::component::query::{ ComponentQuery, ReadOnlyComponentQuery, ComponentFilter };
 use crate::component::archetype::Archetype;
@@ -15,6 +17,7 @@ use core::task::Poll;
 use alloc::collections::BTreeMap;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
+use alloc::sync::Arc;
 
 
 /// A wrapper for several different [`Archetype`]s.
@@ -207,6 +210,8 @@ impl ArchetypeStorage {
 
     /// Gets the corresponding [`Archetype`] (creating it if needed), then adds a row, "spawning" an entity.
     ///
+    /// This does not run [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks. See [`World::spawn`].
+    ///
     /// # Panics
     /// Panics if the given [`ComponentBundle`] is not valid.
     /// See [`BundleValidator`](crate::component::bundle::BundleValidator).
@@ -219,6 +224,8 @@ impl ArchetypeStorage {
 
     /// Gets the corresponding [`Archetype`] (creating it if needed), then adds a row, "spawning an entity", without checking that the given [`ComponentBundle`] is valid.
     ///
+    /// This does not run [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks. See [`World::spawn_unchecked`].
+    ///
     /// # Safety
     /// The caller is responsible for ensuring that the given [`ComponentBundle`] does not violate the archetype rules. See [`BundleValidator`](crate::component::bundle::BundleValidator).
     pub async unsafe fn spawn_unchecked<C : ComponentBundle + 'static>(&self, bundle : C) -> Entity {
@@ -235,6 +242,7 @@ impl ArchetypeStorage {
     /// Gets the corresponding [`Archetype`] (creating it if needed), then adds several rows, "spawning" entities.
     ///
     /// This is more efficient than [`ArchetypeStorage::spawn`], but has the downside of only being able to spawn entities with the same [`ComponentBundle`] type.
+    /// This does not run [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks. See [`World::spawn_batch`].
     ///
     /// # Panics
     /// Panics if the given [`ComponentBundle`]s are not valid.
@@ -249,6 +257,7 @@ impl ArchetypeStorage {
     /// Gets the corresponding [`Archetype`] (creating it if needed), then adds several rows, "spawning" entities, without checking that the given [`ComponentBundle`] is valid.
     ///
     /// This is more efficient than [`ArchetypeStorage::spawn`], but has the downside of only being able to spawn entities with the same [`ComponentBundle`] type.
+    /// This does not run [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks. See [`World::spawn_batch_unchecked`].
     ///
     /// # Safety
     /// The caller is responsible for ensuring that the given [`ComponentBundle`] does not violate the archetype rules. See [`BundleValidator`](crate::component::bundle::BundleValidator).
@@ -268,11 +277,14 @@ impl ArchetypeStorage {
     }
 
     /// Removes a row from an [`Archetype`], if it exists.
+    ///
+    /// This does not run [`Component::ON_REMOVE`](crate::component::Component::ON_REMOVE) hooks. See [`World::despawn`].
+    /// A row which is already being despawned by [`World::despawn`] is left for it to remove.
     pub async fn despawn(&self, entity : Entity) {
         let archetype_id = entity.archetype_id();
         let Some(mut archetype) = FunctionCallFuture::new(|| self.get_mut_by_id(archetype_id)).await else { return };
         let row = entity.archetype_row();
-        if (archetype.has_row(row)) {
+        if (archetype.has_row(row) && ! archetype.is_despawning(row)) {
             // SAFETY: It was checked in the line above that the row exists.
             unsafe{ archetype.despawn_unchecked(row); }
         }
@@ -280,8 +292,10 @@ impl ArchetypeStorage {
 
     /// Resmoves a row from an [`Archetype`] without checking that it exists.
     ///
-    /// # Safety:
-    /// The caller is responsible for ensuring that the [`Archetype`] and row exist.
+    /// This does not run [`Component::ON_REMOVE`](crate::component::Component::ON_REMOVE) hooks. See [`World::despawn_unchecked`].
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that the [`Archetype`] and row exist, and that the row is not being despawned by [`World::despawn`].
     pub async fn despawn_unchecked(&self, entity : Entity) {
         let archetype_id = entity.archetype_id();
         // SAFETY: The caller is responsible for ensuring that the archetype exists.
@@ -290,6 +304,34 @@ impl ArchetypeStorage {
         unsafe{ archetype.despawn_unchecked(entity.archetype_row()); }
     }
 
+    /// Removes a row from an [`Archetype`] if it exists, running any [`Component::ON_REMOVE`](crate::component::Component::ON_REMOVE) hooks with `world` first.
+    ///
+    /// The row is marked as being despawned while the hooks run, so the entity's components can still be queried by them,
+    ///  despawning it again does nothing, and the row can not be reused by another entity until it has been removed.
+    pub(crate) async fn despawn_with_hooks(&self, world : &Arc<World>, entity : Entity) {
+        let archetype_id = entity.archetype_id();
+        let row          = entity.archetype_row();
+        let type_infos   = {
+            let Some(mut archetype) = FunctionCallFuture::new(|| self.get_mut_by_id(archetype_id)).await else { return };
+            if (! archetype.begin_despawn(row)) { return; }
+            archetype.type_infos().collect::<Vec<_>>()
+        };
+        Self::run_hooks(world, entity, type_infos, ComponentTypeInfo::on_remove).await;
+        // SAFETY: The archetype existed above, and archetypes are never removed.
+        let mut archetype = FunctionCallFuture::new(|| unsafe{ self.get_mut_by_id_unchecked(archetype_id) }).await;
+        // SAFETY: The row was marked as being despawned above, so nothing else can have removed it.
+        unsafe{ archetype.despawn_unchecked(row); }
+    }
+
+    /// Runs one kind of [`ComponentHook`] for each of the given components of an entity, in order.
+    pub(crate) async fn run_hooks(world : &Arc<World>, entity : Entity, type_infos : impl IntoIterator<Item = ComponentTypeInfo>, hook : fn(&ComponentTypeInfo) -> Option<ComponentHook>) {
+        for type_info in type_infos {
+            if let Some(hook) = hook(&type_info) {
+                hook(Commands::new(Arc::clone(world)), entity).await;
+            }
+        }
+    }
+
     /// Returns [`Entities`] that match the given [`ReadOnlyComponentQuery`] and [`ComponentFilter`].
     ///
     /// # Panics
@@ -333,3 +375,102 @@ impl ArchetypeStorage {
     }
 
 }
+
+
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use crate::component::{ Component, __box_hook };
+    use crate::component::query::True;
+    use core::sync::atomic::{ AtomicUsize, Ordering };
+    use std::sync::OnceLock;
+    use async_std::task::block_on;
+
+    static WORLD   : OnceLock<Arc<World>> = OnceLock::new();
+    static ADDED   : AtomicUsize          = AtomicUsize::new(0);
+    static REMOVED : AtomicUsize          = AtomicUsize::new(0);
+
+    static REUSE_WORLD   : OnceLock<Arc<World>> = OnceLock::new();
+    static REUSE_REMOVED : AtomicUsize          = AtomicUsize::new(0);
+    static REUSE_SPAWNED : OnceLock<Entity>     = OnceLock::new();
+
+    struct Tracked;
+    impl Component for Tracked {
+        const ON_ADD    : Option<ComponentHook> = Some(|cmds, entity| __box_hook(tracked_added(cmds, entity)));
+        const ON_REMOVE : Option<ComponentHook> = Some(|cmds, entity| __box_hook(tracked_removed(cmds, entity)));
+    }
+
+    async fn tracked_added(_cmds : Commands, _entity : Entity) {
+        ADDED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn tracked_removed(_cmds : Commands, _entity : Entity) {
+        // The component has not been removed yet, so it can still be queried.
+        let live = WORLD.get().unwrap().archetypes().query::<(&Tracked,), True>().await.iter().count();
+        assert_eq!(live + REMOVED.load(Ordering::SeqCst), ADDED.load(Ordering::SeqCst));
+        REMOVED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn hooks_run_on_spawn_and_despawn() { block_on(async {
+        let world = WORLD.get_or_init(|| Arc::new(World::new()));
+        let first = world.spawn((Tracked,)).await;
+        let batch = world.spawn_batch((0..3).map(|_| (Tracked,))).await.collect::<Vec<_>>();
+        assert_eq!(ADDED.load(Ordering::SeqCst), 4);
+        world.despawn(first).await;
+        // Despawning an entity which no longer exists runs no hooks.
+        world.despawn(first).await;
+        assert_eq!(REMOVED.load(Ordering::SeqCst), 1);
+        for entity in batch {
+            // SAFETY: The entity was spawned above and has not been despawned.
+            unsafe{ world.despawn_unchecked(entity).await; }
+        }
+        assert_eq!(REMOVED.load(Ordering::SeqCst), 4);
+        assert_eq!(world.archetypes().query::<(&Tracked,), True>().await.iter().count(), 0);
+    }) }
+
+    struct Reused;
+    impl Component for Reused {
+        const ON_REMOVE : Option<ComponentHook> = Some(|cmds, entity| __box_hook(reused_removed(cmds, entity)));
+    }
+
+    async fn reused_removed(_cmds : Commands, entity : Entity) {
+        if (REUSE_REMOVED.fetch_add(1, Ordering::SeqCst) == 0) {
+            let world = REUSE_WORLD.get().unwrap();
+            // Despawning the entity again does nothing while its hooks are running.
+            world.despawn(entity).await;
+            // The row is still occupied, so this entity can not reuse it.
+            let _ = REUSE_SPAWNED.set(world.spawn((Reused,)).await);
+        }
+    }
+
+    #[test]
+    fn despawning_rows_are_not_reused_while_hooks_run() { block_on(async {
+        let world  = REUSE_WORLD.get_or_init(|| Arc::new(World::new()));
+        let entity = world.spawn((Reused,)).await;
+        world.despawn(entity).await;
+        assert_eq!(REUSE_REMOVED.load(Ordering::SeqCst), 1);
+        let spawned = *REUSE_SPAWNED.get().unwrap();
+        assert_ne!(spawned.archetype_row(), entity.archetype_row());
+        let archetype = world.archetypes().get_ref::<(Reused,)>().await.unwrap();
+        assert!(! archetype.has_row(entity.archetype_row()));
+        assert!(archetype.has_row(spawned.archetype_row()));
+        assert_eq!(archetype.row_count(), 1);
+    }) }
+
+    #[test]
+    fn world_entity_futures_are_send() {
+        fn assert_send<F : Future + Send>(_ : F) { }
+        let world = Arc::new(World::new());
+        assert_send(world.spawn((Tracked,)));
+        assert_send(world.spawn_batch([(Tracked,)]));
+        assert_send(world.despawn(Entity::new(
+            0,
+            #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+            "",
+            0
+        )));
+    }
+
+}
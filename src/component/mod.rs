@@ -39,12 +39,16 @@ pub mod bundle;
 pub mod query;
 
 
+use crate::world::Commands;
+use crate::entity::Entity;
 use core::any::TypeId;
 #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
 use core::any::type_name;
 use core::alloc::Layout;
 use core::ptr::NonNull;
 use core::cmp::Ordering;
+use core::pin::Pin;
+use alloc::boxed::Box;
 
 
 /// A component which can be attached to an entity.
@@ -69,30 +73,73 @@ use core::cmp::Ordering;
 ///     Jump
 /// }
 /// ```
-pub trait Component : Send + Sync + Sized { }
+///
+/// #### Hooks
+///
+/// Functions can be run whenever a component is added to or removed from an entity, using `#[component(on_add = ..., on_remove = ...)]`.
+/// Each hook is an `async fn` which takes [`Commands`] and the [`Entity`].
+/// Components are only ever added when an entity is spawned and removed when it is despawned, so there is no separate `on_insert` hook.
+/// ```rust
+/// use axecs::prelude::*;
+///
+/// #[derive(Component)]
+/// #[component(on_add = register_name, on_remove = unregister_name)]
+/// struct Name(String);
+///
+/// async fn register_name(cmds : Commands, entity : Entity) {
+///     println!("Entity {} was named", entity.archetype_row());
+/// }
+///
+/// async fn unregister_name(cmds : Commands, entity : Entity) {
+///     println!("Entity {} lost its name", entity.archetype_row());
+/// }
+/// ```
+pub trait Component : Send + Sync + Sized {
+
+    /// A hook which runs after a component of this type is added to an entity, when the entity is spawned.
+    const ON_ADD    : Option<ComponentHook> = None;
 
+    /// A hook which runs before a component of this type is removed from an entity, when the entity is despawned.
+    const ON_REMOVE : Option<ComponentHook> = None;
 
-/// Information about a [`Component`] type, such as [`TypeId`], [`Layout`], and drop function.
+}
+
+
+/// A function which runs when a [`Component`] is added to or removed from an entity. See [`Component::ON_ADD`] and [`Component::ON_REMOVE`].
+pub type ComponentHook = fn(Commands, Entity) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Boxes the future returned by a [`ComponentHook`]. Used by the [`Component`] derive macro.
+#[doc(hidden)]
+pub fn __box_hook(future : impl Future<Output = ()> + Send + 'static) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(future)
+}
+
+
+/// Information about a [`Component`] type, such as [`TypeId`], [`Layout`], drop function, and hooks.
 #[derive(Clone, Copy, Debug)]
 pub struct ComponentTypeInfo {
-    type_id : TypeId,
-    layout  : Layout,
-    drop    : unsafe fn(NonNull<u8>) -> (),
+    type_id   : TypeId,
+    layout    : Layout,
+    drop      : unsafe fn(NonNull<u8>) -> (),
+    on_add    : Option<ComponentHook>,
+    on_remove : Option<ComponentHook>,
     #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
-    name    : &'static str
+    name      : &'static str
 }
 
 impl ComponentTypeInfo {
 
     /// Returns the [`ComponentTypeInfo`] for a [`Component`] `C`.
     pub const fn of<C : Component + 'static>() -> Self { Self {
-        type_id : TypeId::of::<C>(),
-        layout  : Layout::new::<C>(),
+        type_id   : TypeId::of::<C>(),
+        layout    : Layout::new::<C>(),
         // SAFETY: The value pointed to by `ptr` is of type `C`. It is safe to assume
         //         that value is of type `C`.
-        drop    : |ptr| unsafe{ ptr.cast::<C>().drop_in_place() },
+        drop      : |ptr| unsafe{ ptr.cast::<C>().drop_in_place() },
+        on_add    : C::ON_ADD,
+        on_remove : C::ON_REMOVE,
         #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
-        name    : type_name::<C>()
+        name      : type_name::<C>()
     } }
 
     /// Returns the [`TypeId`] of the [`Component`].
@@ -113,6 +160,16 @@ impl ComponentTypeInfo {
         self.drop
     }
 
+    /// Returns the [`Component::ON_ADD`] hook of the [`Component`].
+    pub const fn on_add(&self) -> Option<ComponentHook> {
+        self.on_add
+    }
+
+    /// Returns the [`Component::ON_REMOVE`] hook of the [`Component`].
+    pub const fn on_remove(&self) -> Option<ComponentHook> {
+        self.on_remove
+    }
+
     /// Returns the [`type_name`] of the [`Component`].
     #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
     #[doc(cfg(feature = "keep_debug_names"))]
@@ -0,0 +1,189 @@
+//! `trait`s for querying contiguous slices of [`Component`]s from [`Archetype`]s.
+
+
+use crate::component::{ self, Component };
+use crate::component::query::ComponentQuery;
+use crate::component::archetype::Archetype;
+use crate::query::QueryAcquireResult;
+use crate::util::variadic::variadic_no_unit;
+use core::ops::Range;
+#[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+use core::any::type_name;
+
+
+/// A [`ComponentQuery`] which can access a range of consecutive rows as contiguous slices.
+///
+/// See [`Entities::iter_chunks`](crate::entity::Entities::iter_chunks) and [`Entities::iter_chunks_mut`](crate::entity::Entities::iter_chunks_mut).
+///
+/// # Safety
+/// The implementor is responsible for ensuring that the chunks returned cover exactly the rows requested,
+///  and access the same columns as [`ComponentQuery::get_row_ref`] and [`ComponentQuery::get_row_mut`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a valid component chunk query"
+)]
+pub unsafe trait ComponentChunkQuery : ComponentQuery {
+
+    /// The type that this [`ComponentChunkQuery`] returns when iterated immutably.
+    type Chunk<'item>;
+
+    /// The type that this [`ComponentChunkQuery`] returns when iterated mutably.
+    type ChunkMut<'item>;
+
+    /// Gets a range of consecutive rows in the [`Archetype`].
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that:
+    /// - this query does not violate the borrow checker rules.
+    /// - every row in the given range exists.
+    unsafe fn get_chunk_ref<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>>;
+
+    /// Gets a range of consecutive rows in the [`Archetype`].
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that:
+    /// - this query does not violate the borrow checker rules.
+    /// - the given archetype is not borrowed anywhere else. This should be treated as if it is being borrowed mutably.
+    /// - every row in the given range exists.
+    unsafe fn get_chunk_mut<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>>;
+
+}
+
+
+unsafe impl ComponentChunkQuery for () {
+    type Chunk<'item> = ();
+    type ChunkMut<'item> = ();
+
+    unsafe fn get_chunk_ref<'item>(_archetype : &'item Archetype, _rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>> {
+        QueryAcquireResult::Ready(())
+    }
+
+    unsafe fn get_chunk_mut<'item>(_archetype : &'item Archetype, _rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>> {
+        QueryAcquireResult::Ready(())
+    }
+
+}
+
+
+unsafe impl<Q : ComponentChunkQuery> ComponentChunkQuery for Option<Q> {
+    type Chunk<'item> = Option<Q::Chunk<'item>>;
+    type ChunkMut<'item> = Option<Q::ChunkMut<'item>>;
+
+    unsafe fn get_chunk_ref<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        match (unsafe{ <Q as ComponentChunkQuery>::get_chunk_ref(archetype, rows) }) {
+            QueryAcquireResult::Ready(out)          => QueryAcquireResult::Ready(Some(out)),
+            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None),
+            QueryAcquireResult::NotUnique { .. }    => QueryAcquireResult::Ready(None)
+        }
+    }
+
+    unsafe fn get_chunk_mut<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        match (unsafe{ <Q as ComponentChunkQuery>::get_chunk_mut(archetype, rows) }) {
+            QueryAcquireResult::Ready(out)          => QueryAcquireResult::Ready(Some(out)),
+            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None),
+            QueryAcquireResult::NotUnique { .. }    => QueryAcquireResult::Ready(None)
+        }
+    }
+
+}
+
+
+unsafe impl<C : Component + 'static> ComponentChunkQuery for &C {
+    type Chunk<'item> = &'item [C];
+    type ChunkMut<'item> = Self::Chunk<'item>;
+
+    unsafe fn get_chunk_ref<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>> {
+        if let Some(column) = archetype.get_column_ref::<C>() {
+            // SAFETY: The caller is responsible for ensuring that every row in the range exists.
+            QueryAcquireResult::Ready(unsafe{ column.get_slice_ref(rows) })
+        } else {
+            QueryAcquireResult::DoesNotExist {
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                name : type_name::<component::marker::Component<C>>()
+            }
+        }
+    }
+
+    unsafe fn get_chunk_mut<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        unsafe{ Self::get_chunk_ref(archetype, rows) }
+    }
+
+}
+
+
+unsafe impl<C : Component + 'static> ComponentChunkQuery for &mut C {
+    type Chunk<'item> = &'item [C];
+    type ChunkMut<'item> = &'item mut [C];
+
+    unsafe fn get_chunk_ref<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        unsafe{ <&C as ComponentChunkQuery>::get_chunk_ref(archetype, rows) }
+    }
+
+    unsafe fn get_chunk_mut<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>> {
+        if let Some(column) = archetype.get_column_ptr::<C>() {
+            // SAFETY: The caller is responsible for ensuring that every row in the range exists, and
+            //         that the archetype is not borrowed anywhere else.
+            QueryAcquireResult::Ready(unsafe{ &mut*(&*column).get_slice_ptr(rows) })
+        } else {
+            QueryAcquireResult::DoesNotExist {
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                name : type_name::<component::marker::Component<C>>()
+            }
+        }
+    }
+
+}
+
+
+variadic_no_unit!{ #[doc(fake_variadic)] impl_component_chunk_query_for_tuple }
+/// Implements [`ComponentChunkQuery`] for a tuples of those types.
+macro impl_component_chunk_query_for_tuple( $( #[$meta:meta] )* $( $generic:ident ),* $(,)? ) {
+
+    #[allow(non_snake_case)]
+    $( #[ $meta ] )*
+    unsafe impl< $( $generic : ComponentChunkQuery ),* > ComponentChunkQuery for ( $( $generic , )* ) {
+        type Chunk<'item> = ( $( $generic::Chunk<'item> , )* );
+        type ChunkMut<'item> = ( $( $generic::ChunkMut<'item> , )* );
+
+        unsafe fn get_chunk_ref<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::Chunk<'item>> {
+            // SAFETY: The caller is responsible for upholding the safety guarantees.
+            $( let $generic = match (unsafe{ <$generic as ComponentChunkQuery>::get_chunk_ref(archetype, rows.clone()) }) {
+                QueryAcquireResult::Ready(out)            => out,
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::DoesNotExist { name } => { return QueryAcquireResult::DoesNotExist { name }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::DoesNotExist { }      => { return QueryAcquireResult::DoesNotExist { }; }
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::NotUnique { name, count } => { return QueryAcquireResult::NotUnique { name, count }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::NotUnique { count }       => { return QueryAcquireResult::NotUnique { count }; }
+            }; )*
+            QueryAcquireResult::Ready(( $( $generic , )* ))
+        }
+
+        unsafe fn get_chunk_mut<'item>(archetype : &'item Archetype, rows : Range<usize>) -> QueryAcquireResult<Self::ChunkMut<'item>> {
+            // SAFETY: The caller is responsible for upholding the safety guarantees.
+            // SAFETY: As long as this [`ComponentQuery`] does not violate the archetype rules,
+            //         this operation will not access a column that is already mutable accessed
+            //         elsewhere, as each column [`Component`] type stored in the [`Archetype`]
+            //         is unique.
+            $( let $generic = match (unsafe{ <$generic as ComponentChunkQuery>::get_chunk_mut(archetype, rows.clone()) }) {
+                QueryAcquireResult::Ready(out)            => out,
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::DoesNotExist { name } => { return QueryAcquireResult::DoesNotExist { name }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::DoesNotExist { }      => { return QueryAcquireResult::DoesNotExist { }; }
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::NotUnique { name, count } => { return QueryAcquireResult::NotUnique { name, count }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::NotUnique { count }       => { return QueryAcquireResult::NotUnique { count }; }
+            }; )*
+            QueryAcquireResult::Ready(( $( $generic , )* ))
+        }
+
+    }
+
+}
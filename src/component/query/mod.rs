@@ -6,3 +6,88 @@ pub use query::*;
 
 mod filter;
 pub use filter::*;
+
+mod chunk;
+pub use chunk::*;
+
+
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::prelude::*;
+    use crate::component::query::ReadOnlyComponentQuery;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use async_std::task::block_on;
+
+    #[derive(Component)]
+    struct Position(usize);
+
+    #[derive(Component)]
+    struct Velocity(usize);
+
+    #[derive(Component)]
+    struct Level<const N : usize>;
+
+    #[derive(ComponentQuery)]
+    #[allow(dead_code)]
+    struct Viewer {
+        entity   : Entity,
+        position : &'static Position,
+        velocity : Option<&'static Velocity>
+    }
+
+    #[derive(ComponentQuery)]
+    #[allow(dead_code)]
+    struct Mover {
+        position : &'static mut Position,
+        velocity : &'static Velocity
+    }
+
+    #[derive(ComponentQuery)]
+    #[allow(dead_code)]
+    struct AtLevel<const N : usize> {
+        position : &'static Position,
+        level    : &'static Level<N>
+    }
+
+    /// More fields than the largest tuple that the query traits are implemented for.
+    #[derive(ComponentQuery)]
+    #[allow(dead_code)]
+    struct Wide {
+        a : Entity, b : Entity, c : Entity, d : Entity, e : Entity, f : Entity, g : Entity,
+        h : Entity, i : Entity, j : Entity, k : Entity, l : Entity, m : &'static Position
+    }
+
+    fn is_read_only<Q : ReadOnlyComponentQuery>() { }
+
+    #[test]
+    fn derived_component_queries() { block_on(async {
+        is_read_only::<Viewer>();
+        is_read_only::<AtLevel<1>>();
+        let world = Arc::new(World::new());
+        let moving = world.spawn_batch((0..3).map(|i| (Position(i), Velocity(10),))).await.collect::<Vec<_>>();
+        world.spawn((Position(100), Level::<1>,)).await;
+        world.spawn((Position(200), Level::<2>,)).await;
+
+        let mut movers = world.query_mut::<Entities<Mover>>();
+        for item in &mut movers.acquire().await {
+            item.position.0 += item.velocity.0;
+        }
+
+        let mut viewers = world.query::<Entities<Viewer>>();
+        let viewers = viewers.acquire().await;
+        let mut moved = viewers.iter().filter(|item| item.velocity.is_some()).map(|item| (item.entity, item.position.0)).collect::<Vec<_>>();
+        moved.sort_by_key(|(_, position)| *position);
+        assert_eq!(moved, moving.into_iter().zip([10, 11, 12]).collect::<Vec<_>>());
+        assert_eq!(viewers.iter().count(), 5);
+        drop(viewers);
+
+        let mut levels = world.query::<Entities<AtLevel<2>>>();
+        assert_eq!(levels.acquire().await.iter().map(|item| item.position.0).collect::<Vec<_>>(), [200]);
+
+        let mut wide = world.query::<Entities<Wide>>();
+        assert_eq!(wide.acquire().await.iter().filter(|item| item.a == item.l).count(), 5);
+    }) }
+
+}
@@ -82,7 +82,8 @@ unsafe impl<Q : ComponentQuery> ComponentQuery for Option<Q> {
         // SAFETY: TODO
         match (unsafe{ <Q as ComponentQuery>::get_row_ref(archetype, row) }) {
             QueryAcquireResult::Ready(out)          => QueryAcquireResult::Ready(Some(out)),
-            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None)
+            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None),
+            QueryAcquireResult::NotUnique { .. }    => QueryAcquireResult::Ready(None)
         }
     }
 
@@ -90,7 +91,8 @@ unsafe impl<Q : ComponentQuery> ComponentQuery for Option<Q> {
         // SAFETY: TODO
         match (unsafe{ <Q as ComponentQuery>::get_row_mut(archetype, row) }) {
             QueryAcquireResult::Ready(out)          => QueryAcquireResult::Ready(Some(out)),
-            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None)
+            QueryAcquireResult::DoesNotExist { .. } => QueryAcquireResult::Ready(None),
+            QueryAcquireResult::NotUnique { .. }    => QueryAcquireResult::Ready(None)
         }
     }
 
@@ -98,6 +100,7 @@ unsafe impl<Q : ComponentQuery> ComponentQuery for Option<Q> {
         <Q as ComponentQuery>::validate()
     }
 }
+unsafe impl<Q : ReadOnlyComponentQuery> ReadOnlyComponentQuery for Option<Q> { }
 
 
 unsafe impl<C : Component + 'static> ComponentQuery for &C {
@@ -151,7 +154,7 @@ unsafe impl<C : Component + 'static> ComponentQuery for &mut C {
     unsafe fn get_row_mut<'item>(archetype : &'item Archetype, row : usize) -> QueryAcquireResult<Self::ItemMut<'item>> {
         if let Some(column) = archetype.get_column_ptr::<C>() {
             // SAFETY: TODO
-            QueryAcquireResult::Ready(unsafe{ (&mut*column).get_mut(row) })
+            QueryAcquireResult::Ready(unsafe{ &mut*(&*column).get_ptr(row) })
         } else {
             QueryAcquireResult::DoesNotExist {
                 #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
@@ -190,6 +193,10 @@ macro impl_component_query_for_tuple( $( #[$meta:meta] )* $( $generic:ident ),*
                 QueryAcquireResult::DoesNotExist { name } => { return QueryAcquireResult::DoesNotExist { name }; }
                 #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
                 QueryAcquireResult::DoesNotExist { }      => { return QueryAcquireResult::DoesNotExist { }; }
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::NotUnique { name, count } => { return QueryAcquireResult::NotUnique { name, count }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::NotUnique { count }       => { return QueryAcquireResult::NotUnique { count }; }
             }; )*
             QueryAcquireResult::Ready(( $( $generic , )* ))
         }
@@ -206,6 +213,10 @@ macro impl_component_query_for_tuple( $( #[$meta:meta] )* $( $generic:ident ),*
                 QueryAcquireResult::DoesNotExist { name } => { return QueryAcquireResult::DoesNotExist { name }; }
                 #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
                 QueryAcquireResult::DoesNotExist { }      => { return QueryAcquireResult::DoesNotExist { }; }
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                QueryAcquireResult::NotUnique { name, count } => { return QueryAcquireResult::NotUnique { name, count }; }
+                #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                QueryAcquireResult::NotUnique { count }       => { return QueryAcquireResult::NotUnique { count }; }
             }; )*
             QueryAcquireResult::Ready(( $( $generic , )* ))
         }
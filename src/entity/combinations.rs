@@ -0,0 +1,261 @@
+//! Iterators over every unordered combination of entities matched by an [`Entities`] query.
+
+
+use crate::entity::Entities;
+use crate::component::query::{ ComponentQuery, ComponentFilter };
+use crate::component::archetype::Archetype;
+use crate::util::rwlock::RwLockWriteGuard;
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+
+
+impl<Q : ComponentQuery, F : ComponentFilter> Entities<Q, F> {
+
+    /// Returns an [`Iterator`] over every unordered combination of `K` distinct matched entities.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position {
+    ///     x : f32
+    /// }
+    ///
+    /// async fn print_distances(
+    ///     entities : Entities<(Entity, &Position,)>
+    /// ) {
+    ///     for [(a, a_pos,), (b, b_pos,)] in entities.iter_combinations::<2>() {
+    ///         println!("{:?} <-> {:?} : {}", a, b, (a_pos.x - b_pos.x).abs());
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_combinations<const K : usize>(&self) -> EntitiesCombinations<'_, Q, K> {
+        EntitiesCombinations {
+            archetypes : &self.archetypes,
+            indices    : CombinationIndices::new(&self.archetypes),
+            marker     : PhantomData
+        }
+    }
+
+    /// Returns a lending iterator over every unordered combination of `K` distinct matched entities.
+    ///
+    /// Call [`EntitiesCombinationsMut::fetch_next`] to get the next combination.
+    /// Only one combination can be borrowed at a time, so the items never alias.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Body {
+    ///     x     : f32,
+    ///     force : f32
+    /// }
+    ///
+    /// async fn gravity(
+    ///     mut entities : Entities<(&mut Body,)>
+    /// ) {
+    ///     let mut combinations = entities.iter_combinations_mut::<2>();
+    ///     while let Some([(a,), (b,)]) = combinations.fetch_next() {
+    ///         let force = 1.0 / (a.x - b.x).powi(2).max(1.0);
+    ///         a.force += force;
+    ///         b.force -= force;
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_combinations_mut<const K : usize>(&mut self) -> EntitiesCombinationsMut<'_, Q, K> {
+        EntitiesCombinationsMut {
+            archetypes : &self.archetypes,
+            indices    : CombinationIndices::new(&self.archetypes),
+            marker     : PhantomData
+        }
+    }
+
+}
+
+
+/// An [`Iterator`] over every unordered combination of `K` distinct entities matched by an [`Entities`] query.
+///
+/// See [`Entities::iter_combinations`].
+pub struct EntitiesCombinations<'l, Q : ComponentQuery, const K : usize> {
+
+    /// The archetypes held by the [`Entities`] query.
+    archetypes : &'l [RwLockWriteGuard<Archetype>],
+
+    /// The indices of the current combination.
+    indices    : CombinationIndices<K>,
+
+    /// Marker for the query type.
+    marker     : PhantomData<fn(&Archetype, usize) -> Q::Item<'l>>
+
+}
+
+impl<'l, Q : ComponentQuery, const K : usize> Iterator for EntitiesCombinations<'l, Q, K> {
+    type Item = [Q::Item<'l>; K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let archetypes = self.archetypes;
+        let rows       = self.indices.next()?;
+        Some(rows.map(|(archetype, row)|
+            // SAFETY: `archetype` and `row` were taken from an occupied row of a held archetype.
+            //         The archetypes are borrowed immutably for `'l`.
+            unsafe{ Q::get_row_ref(&archetypes[archetype], row).unwrap_unchecked() }
+        ))
+    }
+}
+
+
+/// A lending iterator over every unordered combination of `K` distinct entities matched by an [`Entities`] query.
+///
+/// See [`Entities::iter_combinations_mut`].
+pub struct EntitiesCombinationsMut<'l, Q : ComponentQuery, const K : usize> {
+
+    /// The archetypes held by the [`Entities`] query.
+    archetypes : &'l [RwLockWriteGuard<Archetype>],
+
+    /// The indices of the current combination.
+    indices    : CombinationIndices<K>,
+
+    /// Marker for the query type.
+    marker     : PhantomData<fn(&Archetype, usize) -> Q::ItemMut<'l>>
+
+}
+
+impl<Q : ComponentQuery, const K : usize> EntitiesCombinationsMut<'_, Q, K> {
+
+    /// Returns the next combination of entities, or `None` if every combination has been visited.
+    ///
+    /// The returned items borrow this iterator, so they must be dropped before the next combination can be fetched.
+    pub fn fetch_next(&mut self) -> Option<[Q::ItemMut<'_>; K]> {
+        let archetypes = self.archetypes;
+        let rows       = self.indices.next()?;
+        Some(rows.map(|(archetype, row)|
+            // SAFETY: Every row in a combination is distinct, so no two items alias.
+            //         The archetypes were borrowed mutably from the `Entities` query, and
+            //         the returned items borrow `self` mutably, so only one combination
+            //         can be alive at any time.
+            unsafe{ Q::get_row_mut(&archetypes[archetype], row).unwrap_unchecked() }
+        ))
+    }
+
+}
+
+
+/// Steps through every unordered combination of `K` distinct occupied rows, in lexicographic order.
+struct CombinationIndices<const K : usize> {
+
+    /// Every occupied row, as `(archetype index, row)`.
+    rows    : Vec<(usize, usize)>,
+
+    /// Indices into `rows` of the last returned combination, or `None` if nothing has been returned yet.
+    indices : Option<[usize; K]>,
+
+    /// Whether every combination has been returned.
+    done    : bool
+
+}
+
+impl<const K : usize> CombinationIndices<K> {
+
+    /// Collects the occupied rows of the given archetypes.
+    fn new(archetypes : &[RwLockWriteGuard<Archetype>]) -> Self {
+        let rows = archetypes.iter().enumerate()
+            .flat_map(|(i, archetype)| archetype.rows().map(move |row| (i, row)))
+            .collect::<Vec<_>>();
+        Self {
+            done    : K == 0 || K > rows.len(),
+            rows,
+            indices : None
+        }
+    }
+
+}
+
+impl<const K : usize> Iterator for CombinationIndices<K> {
+    type Item = [(usize, usize); K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if (self.done) { return None; }
+        let len     = self.rows.len();
+        let indices = match (&mut self.indices) {
+            None          => self.indices.insert(core::array::from_fn(|i| i)),
+            Some(indices) => {
+                let Some(i) = (0..K).rev().find(|&i| indices[i] < len - K + i) else {
+                    self.done = true;
+                    return None;
+                };
+                indices[i] += 1;
+                for j in (i + 1)..K {
+                    indices[j] = indices[j - 1] + 1;
+                }
+                indices
+            }
+        };
+        Some(indices.map(|i| self.rows[i]))
+    }
+}
+
+
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use crate::world::World;
+    use crate::component::Component;
+    use alloc::sync::Arc;
+    use alloc::collections::BTreeSet;
+    use async_std::task::block_on;
+
+    struct Id(usize);
+    impl Component for Id { }
+
+    struct Count(usize);
+    impl Component for Count { }
+
+    struct Marker;
+    impl Component for Marker { }
+
+    /// Spawns five entities with an [`Id`] and a [`Count`], split across two archetypes.
+    async fn spawn_entities() -> Arc<World> {
+        let world = Arc::new(World::new());
+        for id in 0..5 {
+            if (id % 2 == 0) { world.spawn((Id(id), Count(0),)).await; }
+            else { world.spawn((Id(id), Count(0), Marker,)).await; }
+        }
+        world
+    }
+
+    #[test]
+    fn combinations_are_distinct_and_unordered() { block_on(async {
+        let world = spawn_entities().await;
+        let mut query = world.query::<Entities<(&Id,)>>();
+        let entities  = query.acquire().await;
+        let pairs = entities.iter_combinations::<2>()
+            .map(|[(a,), (b,)]| (a.0.min(b.0), a.0.max(b.0)))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 10);
+        assert!(pairs.iter().all(|(a, b)| a != b));
+        assert_eq!(pairs.iter().collect::<BTreeSet<_>>().len(), 10);
+        assert_eq!(entities.iter_combinations::<0>().count(), 0);
+        assert_eq!(entities.iter_combinations::<1>().count(), 5);
+        assert_eq!(entities.iter_combinations::<3>().count(), 10);
+        assert_eq!(entities.iter_combinations::<5>().count(), 1);
+        assert_eq!(entities.iter_combinations::<6>().count(), 0);
+    }); }
+
+    #[test]
+    fn mutable_combinations_visit_every_pair() { block_on(async {
+        let world = spawn_entities().await;
+        let mut query = world.query_mut::<Entities<(&mut Count,)>>();
+        let mut entities = query.acquire().await;
+        let mut combinations = entities.iter_combinations_mut::<2>();
+        while let Some([(a,), (b,)]) = combinations.fetch_next() {
+            a.0 += 1;
+            b.0 += 1;
+        }
+        assert!(entities.iter().all(|(count,)| count.0 == 4));
+        assert!(entities.iter_combinations_mut::<6>().fetch_next().is_none());
+    }); }
+
+}
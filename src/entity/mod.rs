@@ -33,10 +33,18 @@
 mod query;
 pub use query::*;
 
+mod combinations;
+pub use combinations::*;
+
+mod single;
+pub use single::*;
+
 
 #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
 use crate::util::unqualified::UnqualifiedTypeName;
 use core::fmt;
+use core::cmp::Ordering;
+use core::hash::{ Hash, Hasher };
 
 
 /// Lightweight identifier of an entity.
@@ -100,6 +108,33 @@ impl Entity {
 
 }
 
+impl PartialEq for Entity {
+    fn eq(&self, other : &Self) -> bool {
+        self.archetype_id == other.archetype_id && self.archetype_row == other.archetype_row
+    }
+}
+
+impl Eq for Entity { }
+
+impl PartialOrd for Entity {
+    fn partial_cmp(&self, other : &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entity {
+    fn cmp(&self, other : &Self) -> Ordering {
+        (self.archetype_id, self.archetype_row).cmp(&(other.archetype_id, other.archetype_row))
+    }
+}
+
+impl Hash for Entity {
+    fn hash<H : Hasher>(&self, state : &mut H) {
+        self.archetype_id.hash(state);
+        self.archetype_row.hash(state);
+    }
+}
+
 impl fmt::Debug for Entity {
     fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Entity(")?;
@@ -2,25 +2,37 @@
 
 
 use crate::world::World;
-use crate::component::query::{ ComponentQuery, ReadOnlyComponentQuery, ComponentFilter, True };
+use crate::entity::Entity;
+use crate::component::query::{ ComponentQuery, ReadOnlyComponentQuery, ComponentChunkQuery, ComponentFilter, True };
 use crate::component::archetype::{ ArchetypeStorage, Archetype };
 use crate::system::SystemId;
 use crate::query::{ Query, ReadOnlyQuery, QueryAcquireResult, QueryValidator };
 use crate::util::rwlock::RwLockWriteGuard;
 use core::task::Poll;
+use core::future::poll_fn;
+use core::ops::AsyncFn;
 use core::ops::{ Deref, DerefMut };
 use core::mem::MaybeUninit;
 use core::cell::UnsafeCell;
+use core::borrow::Borrow;
 use core::marker::PhantomData;
+use alloc::boxed::Box;
 use alloc::vec::Vec;
+use alloc::collections::BTreeSet;
 use alloc::sync::Arc;
+#[cfg(not(feature = "no_std"))]
+use core::sync::atomic::{ AtomicUsize, Ordering as AtomicOrdering };
+#[cfg(not(feature = "no_std"))]
+use core::panic::AssertUnwindSafe;
+#[cfg(not(feature = "no_std"))]
+use std::{ panic, thread };
 
 
 /// TODO: Doc comments
 pub struct Entities<Q : ComponentQuery, F : ComponentFilter = True> {
 
     /// TODO: Doc comments
-    archetypes : Vec<RwLockWriteGuard<Archetype>>,
+    pub(super) archetypes : Vec<RwLockWriteGuard<Archetype>>,
 
     /// TODO: Doc comments
     marker_a  : PhantomData<fn(&Archetype, usize) -> Q::ItemMut<'static>>,
@@ -85,7 +97,8 @@ unsafe impl<Q : ComponentQuery + 'static, F : ComponentFilter> Query for Entitie
     }
 
     fn validate() -> QueryValidator {
-        <Q as ComponentQuery>::validate()
+        // The matching archetypes stay locked while this is held, even if `Q` requests no components.
+        QueryValidator::join(QueryValidator::of_immutable::<ArchetypeStorage>(), <Q as ComponentQuery>::validate())
     }
 
 }
@@ -95,6 +108,16 @@ unsafe impl<Q : ReadOnlyComponentQuery + 'static, F : ComponentFilter> ReadOnlyQ
 
 impl<Q : ComponentQuery, F : ComponentFilter> Entities<Q, F> {
 
+    /// Returns the number of entities matched by this query.
+    pub fn len(&self) -> usize {
+        self.archetypes.iter().map(|archetype| archetype.row_count()).sum()
+    }
+
+    /// Returns `true` if no entities are matched by this query.
+    pub fn is_empty(&self) -> bool {
+        self.archetypes.iter().all(|archetype| archetype.row_count() == 0)
+    }
+
     /// TODO: Doc comments
     pub fn iter(&self) -> impl Iterator<Item = Q::Item<'_>> {
         <&Self as IntoIterator>::into_iter(self)
@@ -105,8 +128,320 @@ impl<Q : ComponentQuery, F : ComponentFilter> Entities<Q, F> {
         <&mut Self as IntoIterator>::into_iter(self)
     }
 
+    /// Returns an [`Iterator`] over the given entities which are matched by this query.
+    ///
+    /// Entities which are not matched by this query are skipped.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Children {
+    ///     entities : Vec<Entity>
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Name {
+    ///     name : String
+    /// }
+    ///
+    /// async fn print_children(
+    ///     parents  : Entities<(&Children,)>,
+    ///     children : Entities<(&Name,)>
+    /// ) {
+    ///     for (parent,) in &parents {
+    ///         for (name,) in children.iter_many(&parent.entities) {
+    ///             println!("{}", name.name);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_many<'l, E : Borrow<Entity>>(&'l self, entities : impl IntoIterator<Item = E> + 'l) -> impl Iterator<Item = Q::Item<'l>> {
+        entities.into_iter().filter_map(|entity| {
+            let (archetype, row) = self.find_entity(entity.borrow())?;
+            // SAFETY: `find_entity` only returns occupied rows of held archetypes.
+            Some(unsafe{ Q::get_row_ref(archetype, row).unwrap_unchecked() })
+        })
+    }
+
+    /// Returns an [`Iterator`] over the given entities which are matched by this query.
+    ///
+    /// Entities which are not matched by this query are skipped.
+    ///
+    /// ### Panics
+    /// Panics if the same entity is given more than once.
+    #[track_caller]
+    pub fn iter_many_mut<E : Borrow<Entity>>(&mut self, entities : impl IntoIterator<Item = E>) -> impl Iterator<Item = Q::ItemMut<'_>> {
+        let entities = entities.into_iter().map(|entity| *entity.borrow()).collect::<Vec<_>>();
+        let mut seen = BTreeSet::new();
+        for entity in &entities {
+            if (! seen.insert(*entity)) {
+                panic!("Entities::iter_many_mut was given duplicate entity {:?}", entity);
+            }
+        }
+        let this = &*self;
+        entities.into_iter().filter_map(move |entity| {
+            let (archetype, row) = this.find_entity(&entity)?;
+            // SAFETY: `find_entity` only returns occupied rows of held archetypes. Each entity
+            //         was checked to be unique above, so no two items alias. `self` is borrowed
+            //         mutably, preventing the archetypes from being accessed elsewhere.
+            Some(unsafe{ Q::get_row_mut(archetype, row).unwrap_unchecked() })
+        })
+    }
+
+    /// Finds the held archetype and occupied row of an entity, if it is matched by this query.
+    fn find_entity(&self, entity : &Entity) -> Option<(&Archetype, usize)> {
+        let archetype = self.archetypes.iter().find(|archetype| archetype.archetype_id() == entity.archetype_id())?;
+        let row       = entity.archetype_row();
+        archetype.has_row(row).then_some((&**archetype, row))
+    }
+
+    /// Runs the async function `f` on every matched entity, driving up to `limit` of the returned futures concurrently.
+    ///
+    /// The archetype locks held by this [`Entities`] stay held until every future has completed.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Config {
+    ///     path : String
+    /// }
+    ///
+    /// async fn reload_configs(
+    ///     mut entities : Entities<(&mut Config,)>
+    /// ) {
+    ///     entities.for_each_concurrent(16, async |(config,)| {
+    ///         # async fn read_to_string(path : &str) -> String { path.to_string() }
+    ///         config.path = read_to_string(&config.path).await;
+    ///     }).await;
+    /// }
+    /// ```
+    pub async fn for_each_concurrent<'l, Func>(&'l mut self, limit : usize, f : Func)
+    where
+        Func : AsyncFn(Q::ItemMut<'l>)
+    {
+        let     f         = &f;
+        let     limit     = limit.max(1);
+        let mut items     = self.iter_mut();
+        let mut exhausted = false;
+        let mut running   = Vec::with_capacity(limit);
+        poll_fn(|ctx| {
+            loop {
+                while (! exhausted) && running.len() < limit {
+                    match (items.next()) {
+                        Some(item) => { running.push(Box::pin(f(item))); },
+                        None       => { exhausted = true; }
+                    }
+                }
+                let running_before = running.len();
+                running.retain_mut(|fut| fut.as_mut().poll(ctx).is_pending());
+                if (exhausted && running.is_empty()) {
+                    return Poll::Ready(());
+                }
+                if (exhausted || running.len() == running_before) {
+                    return Poll::Pending;
+                }
+            }
+        }).await
+    }
+
+}
+
+
+impl<Q : ComponentChunkQuery, F : ComponentFilter> Entities<Q, F> {
+
+    /// Returns an [`Iterator`] over contiguous chunks of the matched entities.
+    ///
+    /// Each chunk covers a run of consecutive populated rows in a single archetype,
+    ///  with each queried component returned as a slice.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// async fn total_health(
+    ///     entities : Entities<(&Health,)>
+    /// ) {
+    ///     let mut total = 0;
+    ///     for (healths,) in entities.iter_chunks() {
+    ///         total += healths.iter().map(|health| health.0).sum::<u32>();
+    ///     }
+    ///     println!("{}", total);
+    /// }
+    /// ```
+    pub fn iter_chunks(&self) -> impl Iterator<Item = Q::Chunk<'_>> {
+        self.archetypes.iter().flat_map(|archetype| {
+            let archetype = &**archetype;
+            // SAFETY: `self` is borrowed immutably, and `row_ranges` only returns populated rows.
+            //         Every held archetype is a superset of `Q`.
+            archetype.row_ranges().map(move |rows| unsafe{ Q::get_chunk_ref(archetype, rows).unwrap_unchecked() })
+        })
+    }
+
+    /// Returns an [`Iterator`] over contiguous mutable chunks of the matched entities.
+    ///
+    /// Each chunk covers a run of consecutive populated rows in a single archetype,
+    ///  with each queried component returned as a slice.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Velocity(f32);
+    ///
+    /// async fn apply_velocity(
+    ///     mut entities : Entities<(&mut Position, &Velocity)>
+    /// ) {
+    ///     for (positions, velocities) in entities.iter_chunks_mut() {
+    ///         for (position, velocity) in positions.iter_mut().zip(velocities) {
+    ///             position.0 += velocity.0;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_chunks_mut(&mut self) -> impl Iterator<Item = Q::ChunkMut<'_>> {
+        self.archetypes.iter_mut().flat_map(|archetype| {
+            let archetype = &**archetype;
+            // SAFETY: `self` is borrowed mutably, `row_ranges` only returns populated rows,
+            //         and the ranges never overlap. Every held archetype is a superset of `Q`.
+            archetype.row_ranges().map(move |rows| unsafe{ Q::get_chunk_mut(archetype, rows).unwrap_unchecked() })
+        })
+    }
+
+}
+
+
+#[cfg(not(feature = "no_std"))]
+#[doc(cfg(not(feature = "no_std")))]
+impl<Q : ComponentQuery, F : ComponentFilter> Entities<Q, F> {
+
+    /// Runs `f` on every matched entity, splitting the rows into batches of up to `batch_size` which are processed in parallel.
+    ///
+    /// Batches are handed out to a pool of scoped threads, sized by [`available_parallelism`](std::thread::available_parallelism).
+    /// Each batch covers consecutive populated rows of a single archetype.
+    /// This call blocks until every batch has been processed.
+    ///
+    /// ### Panics
+    /// Panics if `batch_size` is `0`, or if `f` panics.
+    #[track_caller]
+    pub fn par_for_each<Func>(&self, batch_size : usize, f : Func)
+    where
+        Func : for<'item> Fn(Q::Item<'item>) + Sync
+    {
+        // SAFETY: Each row is only visited once, and only immutable references are handed out.
+        self.par_for_each_batch(batch_size, |archetype, row| f(unsafe{ Q::get_row_ref(archetype, row).unwrap_unchecked() }));
+    }
+
+    /// Runs `f` on every matched entity, splitting the rows into batches of up to `batch_size` which are processed in parallel.
+    ///
+    /// Batches are handed out to a pool of scoped threads, sized by [`available_parallelism`](std::thread::available_parallelism).
+    /// Each batch covers consecutive populated rows of a single archetype.
+    /// This call blocks until every batch has been processed.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position {
+    ///     x : f32
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Velocity {
+    ///     x : f32
+    /// }
+    ///
+    /// async fn apply_velocity(
+    ///     mut entities : Entities<(&mut Position, &Velocity,)>
+    /// ) {
+    ///     entities.par_for_each_mut(1024, |(position, velocity,)| {
+    ///         position.x += velocity.x;
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// ### Panics
+    /// Panics if `batch_size` is `0`, or if `f` panics.
+    #[track_caller]
+    pub fn par_for_each_mut<Func>(&mut self, batch_size : usize, f : Func)
+    where
+        Func : for<'item> Fn(Q::ItemMut<'item>) + Sync
+    {
+        // SAFETY: Each row is only visited once, so no two items alias. `self` is borrowed
+        //         mutably, preventing the archetypes from being accessed elsewhere.
+        self.par_for_each_batch(batch_size, |archetype, row| f(unsafe{ Q::get_row_mut(archetype, row).unwrap_unchecked() }));
+    }
+
+    /// Splits the matched rows into batches of up to `batch_size`, and runs `f` on each row from a pool of scoped threads.
+    #[track_caller]
+    fn par_for_each_batch<Func>(&self, batch_size : usize, f : Func)
+    where
+        Func : Fn(&Archetype, usize) + Sync
+    {
+        if (batch_size == 0) {
+            panic!("Entities::par_for_each batch size must be greater than 0");
+        }
+
+        let batches = self.archetypes.iter().flat_map(|archetype| {
+            let archetype = SyncArchetype(archetype);
+            archetype.0.row_ranges().flat_map(move |rows| {
+                let end = rows.end;
+                rows.step_by(batch_size).map(move |start| (archetype, start..(start + batch_size).min(end)))
+            })
+        }).collect::<Vec<_>>();
+        if (batches.is_empty()) { return; }
+
+        let workers    = thread::available_parallelism().map_or(1, |workers| workers.get()).min(batches.len());
+        let next_batch = AtomicUsize::new(0);
+        let run_worker = || panic::catch_unwind(AssertUnwindSafe(|| {
+            while let Some((archetype, rows)) = batches.get(next_batch.fetch_add(1, AtomicOrdering::Relaxed)) {
+                for row in rows.clone() {
+                    f(archetype.0, row);
+                }
+            }
+        }));
+        // Panics are caught in each worker so that the original payload can be resumed, rather than the scope's own panic.
+        let payload = thread::scope(|scope| {
+            let workers = (1..workers).map(|_| scope.spawn(run_worker)).collect::<Vec<_>>();
+            let mut payload = run_worker().err();
+            for worker in workers {
+                if let Ok(Err(err)) = worker.join() {
+                    payload.get_or_insert(err);
+                }
+            }
+            payload
+        });
+        if let Some(payload) = payload {
+            panic::resume_unwind(payload);
+        }
+    }
+
 }
 
+/// An [`Archetype`] reference which can be shared between the worker threads of [`Entities::par_for_each_batch`].
+#[cfg(not(feature = "no_std"))]
+#[derive(Clone, Copy)]
+struct SyncArchetype<'l>(&'l Archetype);
+
+// SAFETY: The worker threads only ever access distinct rows of the archetype.
+#[cfg(not(feature = "no_std"))]
+unsafe impl Send for SyncArchetype<'_> { }
+
+// SAFETY: The worker threads only ever access distinct rows of the archetype.
+#[cfg(not(feature = "no_std"))]
+unsafe impl Sync for SyncArchetype<'_> { }
+
 
 impl<'l, Q : ComponentQuery, F : ComponentFilter> IntoIterator for &'l Entities<Q, F> {
     type Item     = Q::Item<'l>;
@@ -190,3 +525,185 @@ impl<Q : ComponentQuery> Drop for EntitiesEntry<Q> {
         unsafe{ self.entry.assume_init_drop(); }
     }
 }
+
+
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use crate::component::Component;
+    use crate::component::query::{ With, Without };
+    use async_std::task::block_on;
+
+    struct Visits(usize);
+    impl Component for Visits { }
+
+    struct Marker;
+    impl Component for Marker { }
+
+    /// Spawns entities across two archetypes, leaving gaps between some of their rows.
+    async fn spawn_entities(world : &Arc<World>) -> usize {
+        let mut spawned  = 0;
+        let mut entities = Vec::new();
+        for _ in 0..50 {
+            entities.push(world.spawn((Visits(0),)).await);
+        }
+        // Despawned rows are reused by the next spawn, so only despawn once every row is occupied.
+        for (i, entity) in entities.into_iter().enumerate() {
+            if (i % 4 == 0) { world.despawn(entity).await; } else { spawned += 1; }
+        }
+        for _ in 0..37 {
+            world.spawn((Visits(0), Marker,)).await;
+            spawned += 1;
+        }
+        spawned
+    }
+
+    #[test]
+    fn par_for_each_visits_every_row_once() { block_on(async {
+        let world   = Arc::new(World::new());
+        let spawned = spawn_entities(&world).await;
+        let mut query = world.query_mut::<Entities<(&mut Visits,)>>();
+        let mut entities = query.acquire().await;
+        for (i, batch_size) in [1, 3, 16, 37, 1000].into_iter().enumerate() {
+            entities.par_for_each_mut(batch_size, |(visits,)| visits.0 += 1);
+            assert!(entities.iter().all(|(visits,)| visits.0 == i + 1));
+            let count = AtomicUsize::new(0);
+            entities.par_for_each(batch_size, |_| { count.fetch_add(1, AtomicOrdering::Relaxed); });
+            assert_eq!(count.into_inner(), spawned);
+        }
+    }); }
+
+    #[test]
+    #[should_panic(expected = "batch size must be greater than 0")]
+    fn par_for_each_rejects_empty_batches() { block_on(async {
+        let world = Arc::new(World::new());
+        spawn_entities(&world).await;
+        let mut query = world.query_mut::<Entities<(&Visits,)>>();
+        query.acquire().await.par_for_each(0, |_| { });
+    }); }
+
+    #[test]
+    #[should_panic(expected = "visited a marked entity")]
+    fn par_for_each_propagates_panics() { block_on(async {
+        let world = Arc::new(World::new());
+        spawn_entities(&world).await;
+        let mut query = world.query_mut::<Entities<(&Visits, Option<&Marker>)>>();
+        query.acquire().await.par_for_each(4, |(_, marker)| {
+            if (marker.is_some()) { panic!("visited a marked entity"); }
+        });
+    }); }
+
+    #[test]
+    fn for_each_concurrent_bounds_running_futures() { block_on(async {
+        let world = Arc::new(World::new());
+        for _ in 0..20 {
+            world.spawn((Visits(0),)).await;
+        }
+        let mut query = world.query_mut::<Entities<(&mut Visits,)>>();
+        let mut entities = query.acquire().await;
+        for (limit, expected) in [(4, 4), (0, 1), (100, 20)] {
+            let running     = AtomicUsize::new(0);
+            let max_running = AtomicUsize::new(0);
+            entities.for_each_concurrent(limit, async |(visits,)| {
+                let now = running.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+                max_running.fetch_max(now, AtomicOrdering::Relaxed);
+                async_std::task::yield_now().await;
+                visits.0 += 1;
+                running.fetch_sub(1, AtomicOrdering::Relaxed);
+            }).await;
+            assert_eq!(max_running.into_inner(), expected);
+        }
+        assert!(entities.iter().all(|(visits,)| visits.0 == 3));
+    }); }
+
+    #[test]
+    fn iter_many_skips_unmatched_entities() { block_on(async {
+        let world  = Arc::new(World::new());
+        let first  = world.spawn((Visits(1), Marker,)).await;
+        let second = world.spawn((Visits(2),)).await;
+        let marker = world.spawn((Marker,)).await;
+        let mut query = world.query_mut::<Entities<(&mut Visits,)>>();
+        let mut entities = query.acquire().await;
+        let order = [second, marker, first];
+        assert_eq!(entities.iter_many(&order).map(|(visits,)| visits.0).collect::<Vec<_>>(), [2, 1]);
+        for (visits,) in entities.iter_many_mut(order) {
+            visits.0 *= 10;
+        }
+        assert_eq!(entities.iter_many([first, second]).map(|(visits,)| visits.0).collect::<Vec<_>>(), [10, 20]);
+    }); }
+
+    #[test]
+    #[should_panic(expected = "was given duplicate entity")]
+    fn iter_many_mut_rejects_duplicates() { block_on(async {
+        let world  = Arc::new(World::new());
+        let entity = world.spawn((Visits(0),)).await;
+        let mut query = world.query_mut::<Entities<(&mut Visits,)>>();
+        query.acquire().await.iter_many_mut([entity, entity]).for_each(|_| { });
+    }); }
+
+    #[test]
+    fn iter_chunks_cover_every_row() { block_on(async {
+        let world   = Arc::new(World::new());
+        let spawned = spawn_entities(&world).await;
+        let mut query = world.query_mut::<Entities<(&mut Visits, Option<&Marker>)>>();
+        let mut entities = query.acquire().await;
+        for (i, (visits, _)) in entities.iter_chunks_mut().enumerate() {
+            for visit in visits { visit.0 = i + 1; }
+        }
+        let mut chunks = entities.iter_chunks().collect::<Vec<_>>();
+        // The unmarked archetype has a gap every fourth row, the marked archetype has none.
+        assert_eq!(chunks.len(), 14);
+        assert_eq!(chunks.iter().map(|(visits, _)| visits.len()).sum::<usize>(), spawned);
+        chunks.retain(|(_, markers)| markers.is_some());
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0.len(), 37);
+        assert_eq!(chunks[0].1.unwrap().len(), 37);
+        // Every chunk was written to separately.
+        let mut seen = BTreeSet::new();
+        for (visits, _) in entities.iter_chunks() {
+            assert!(visits.iter().all(|visit| visit.0 == visits[0].0));
+            assert!(seen.insert(visits[0].0));
+        }
+    }); }
+
+    #[test]
+    fn len_counts_matching_rows() { block_on(async {
+        let world = Arc::new(World::new());
+        {
+            let mut query    = world.query_mut::<Entities<(&Visits,)>>();
+            let     entities = query.acquire().await;
+            assert_eq!(entities.len(), 0);
+            assert!(entities.is_empty());
+        }
+        let spawned = spawn_entities(&world).await;
+        {
+            let mut query    = world.query_mut::<Entities<(&Visits,)>>();
+            let     entities = query.acquire().await;
+            assert_eq!(entities.len(), spawned);
+            assert!(! entities.is_empty());
+        }
+        {
+            let mut query    = world.query_mut::<Entities<(&Visits,), With<Marker>>>();
+            let     entities = query.acquire().await;
+            assert_eq!(entities.len(), 37);
+        }
+        {
+            let mut query    = world.query_mut::<Entities<(&Visits,), Without<Marker>>>();
+            let     entities = query.acquire().await;
+            assert_eq!(entities.len(), spawned - 37);
+        }
+        {
+            let mut query  = world.query_mut::<Entities<(Entity,), With<Marker>>>();
+            let     marked = query.acquire().await.iter().map(|(entity,)| entity).collect::<Vec<_>>();
+            drop(query);
+            for entity in marked { world.despawn(entity).await; }
+        }
+        // The marked archetype still exists, but has no rows left.
+        let mut query    = world.query_mut::<Entities<(&Visits,), With<Marker>>>();
+        let     entities = query.acquire().await;
+        assert_eq!(entities.len(), 0);
+        assert!(entities.is_empty());
+    }); }
+
+}
@@ -0,0 +1,177 @@
+//! A query for exactly one matching entity.
+
+
+use crate::world::World;
+use crate::entity::{ Entities, EntitiesEntry };
+use crate::component::query::{ ComponentQuery, ReadOnlyComponentQuery, ComponentFilter, True };
+use crate::component::archetype::ArchetypeStorage;
+use crate::system::SystemId;
+use crate::query::{ Query, ReadOnlyQuery, QueryAcquireResult, QueryValidator };
+use core::task::Poll;
+use core::ops::{ Deref, DerefMut };
+use core::marker::PhantomData;
+#[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+use core::any::type_name;
+use alloc::sync::Arc;
+
+
+/// A [`Query`] which resolves to exactly one entity matching `Q` and `F`.
+///
+/// If no entities match, this query does not exist. If more than one entity matches,
+///  acquiring it fails with [`QueryAcquireResult::NotUnique`]. `Option<Single<Q, F>>`
+///  can be used to handle both cases.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Player;
+///
+/// #[derive(Component)]
+/// struct Health {
+///     current : f32
+/// }
+///
+/// async fn heal_player(
+///     mut player : Single<(&mut Health,), With<Player>>
+/// ) {
+///     let (health,) = &mut *player;
+///     health.current += 1.0;
+/// }
+///
+/// async fn print_player_health(
+///     player : Option<Single<(&Health,), With<Player>>>
+/// ) {
+///     if let Some(player) = player {
+///         println!("{}", player.0.current);
+///     }
+/// }
+/// ```
+///
+/// ### Panics
+/// Acquiring this query panics if exactly one entity does not match, unless it is wrapped in an [`Option`].
+pub struct Single<Q : ComponentQuery, F : ComponentFilter = True> {
+
+    /// The matching entity.
+    entry  : EntitiesEntry<Q::AsStatic>,
+
+    /// Marker for the filter type.
+    marker : PhantomData<fn(F) -> bool>
+
+}
+
+unsafe impl<Q : ComponentQuery + 'static, F : ComponentFilter> Query for Single<Q, F> {
+    type Item = Single<Q, F>;
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+
+    #[track_caller]
+    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        let Poll::Ready(entities) = (unsafe{ Entities::<Q, F>::acquire_archetypes_unchecked(world.archetypes()) }) else {
+            return Poll::Pending;
+        };
+        let count = entities.len();
+        if (count > 1) {
+            return Poll::Ready(QueryAcquireResult::NotUnique {
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                name : type_name::<Self>(),
+                count
+            });
+        }
+        let Some(entry) = entities.into_iter().next() else {
+            return Poll::Ready(QueryAcquireResult::DoesNotExist {
+                #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                name : type_name::<Self>()
+            });
+        };
+        Poll::Ready(QueryAcquireResult::Ready(Single {
+            entry,
+            marker : PhantomData
+        }))
+    }
+
+    fn validate() -> QueryValidator {
+        // The matching archetypes stay locked while this is held, even if `Q` requests no components.
+        QueryValidator::join(QueryValidator::of_immutable::<ArchetypeStorage>(), <Q as ComponentQuery>::validate())
+    }
+
+}
+
+unsafe impl<Q : ReadOnlyComponentQuery + 'static, F : ComponentFilter> ReadOnlyQuery for Single<Q, F> { }
+
+
+impl<Q : ComponentQuery, F : ComponentFilter> Single<Q, F> {
+
+    /// Returns the underlying [`EntitiesEntry`].
+    pub fn into_inner(self) -> EntitiesEntry<Q::AsStatic> {
+        self.entry
+    }
+
+}
+
+impl<Q : ComponentQuery, F : ComponentFilter> Deref for Single<Q, F> {
+    type Target = <Q::AsStatic as ComponentQuery>::ItemMut<'static>;
+    fn deref(&self) -> &Self::Target {
+        &self.entry
+    }
+}
+
+impl<Q : ComponentQuery, F : ComponentFilter> DerefMut for Single<Q, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entry
+    }
+}
+
+
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use crate::component::Component;
+    use async_std::task::block_on;
+
+    struct Health(u32);
+    impl Component for Health { }
+
+    #[test]
+    fn option_single_matches_exactly_one() { block_on(async {
+        let world = Arc::new(World::new());
+        assert!(world.query::<Option<Single<(&Health,)>>>().acquire().await.is_none());
+
+        let entity = world.spawn((Health(3),)).await;
+        {
+            let single = world.query::<Option<Single<(&Health,)>>>().acquire().await;
+            assert_eq!(single.expect("one entity matched").0.0, 3);
+        }
+        {
+            let mut single = world.query_mut::<Single<(&mut Health,)>>();
+            single.acquire().await.0.0 += 1;
+        }
+        assert_eq!(world.query::<Single<(&Health,)>>().acquire().await.0.0, 4);
+
+        world.spawn((Health(5),)).await;
+        assert!(world.query::<Option<Single<(&Health,)>>>().acquire().await.is_none());
+
+        world.despawn(entity).await;
+        assert_eq!(world.query::<Single<(&Health,)>>().acquire().await.0.0, 5);
+    }); }
+
+    #[test]
+    #[should_panic(expected = "entities matched instead of exactly one")]
+    fn single_panics_on_many_matches() { block_on(async {
+        let world = Arc::new(World::new());
+        world.spawn((Health(1),)).await;
+        world.spawn((Health(2),)).await;
+        world.query::<Single<(&Health,)>>().acquire().await;
+    }); }
+
+    #[test]
+    #[should_panic(expected = "requested non-existent Single<")]
+    fn single_panics_on_no_matches() { block_on(async {
+        let world = Arc::new(World::new());
+        world.query::<Single<(&Health,)>>().acquire().await;
+    }); }
+
+}
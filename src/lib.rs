@@ -58,10 +58,10 @@ pub mod prelude {
     pub use crate::app::plugin::{ CycleSchedulerPlugin, CtrlCPlugin };
 
     #[doc(inline)]
-    pub use crate::world::{ World, Commands };
+    pub use crate::world::{ World, WorldMut, WorldRef, Commands };
 
     #[doc(inline)]
-    pub use crate::resource::Res;
+    pub use crate::resource::{ Res, NonSend };
 
     /// TODO: Docs
     #[cfg(feature = "derive")]
@@ -86,7 +86,7 @@ pub mod prelude {
     pub use axecs_macro::Resource;
 
     #[doc(inline)]
-    pub use crate::entity::{ Entity, Entities };
+    pub use crate::entity::{ Entity, Entities, Single };
 
     /// Implements [`Component`](crate::component::Component) on an item.
     ///
@@ -138,14 +138,128 @@ pub mod prelude {
     #[doc(cfg(feature = "derive"))]
     pub use axecs_macro::Bundle;
 
+    /// Implements [`ComponentQuery`](crate::component::query::ComponentQuery) on a struct with named fields.
+    ///
+    /// Every field must itself be a [`ComponentQuery`](crate::component::query::ComponentQuery), using `'static` references.
+    /// Two structs are generated alongside it, `{Name}Item` and `{Name}ItemMut`, which are returned when iterated immutably and mutably.
+    /// The struct itself is never constructed, so the compiler may warn that its fields are never read. Add `#[allow(dead_code)]` to it if so.
+    ///
+    /// #### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position {
+    ///     x : f32
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Velocity {
+    ///     x : f32
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Mass {
+    ///     amount : f32
+    /// }
+    ///
+    /// #[derive(ComponentQuery)]
+    /// struct Mover {
+    ///     entity   : Entity,
+    ///     position : &'static mut Position,
+    ///     velocity : &'static Velocity,
+    ///     mass     : Option<&'static Mass>
+    /// }
+    ///
+    /// async fn apply_velocity(
+    ///     mut entities : Entities<Mover>
+    /// ) {
+    ///     for item in &mut entities {
+    ///         let mass = item.mass.map_or(1.0, |mass| mass.amount);
+    ///         item.position.x += item.velocity.x / mass;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Fields can not borrow for a lifetime other than `'static`:
+    /// ```rust compile_fail
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position {
+    ///     x : f32
+    /// }
+    ///
+    /// #[derive(ComponentQuery)]
+    /// struct Viewer<'l> {
+    ///     position : &'l Position
+    /// }
+    /// ```
+    #[cfg(feature = "derive")]
+    #[doc(cfg(feature = "derive"))]
+    pub use axecs_macro::ComponentQuery;
+
     #[doc(inline)]
     pub use crate::component::query::{ With, Without, And, Nand, Or, Nor, Xor, Xnor };
 
+    /// Implements [`Query`](crate::query::Query) on a struct whose fields are all queries, so that they can be requested as a single system parameter.
+    ///
+    /// #### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Resource)]
+    /// struct Config {
+    ///     volume : f32
+    /// }
+    ///
+    /// #[derive(Query)]
+    /// struct CommonParams<'l> {
+    ///     cmds   : Commands,
+    ///     config : Res<&'l Config>
+    /// }
+    ///
+    /// async fn play_sound(
+    ///     params : CommonParams<'_>
+    /// ) {
+    ///     println!("Playing at volume {}", params.config.volume);
+    ///     params.cmds.exit(AppExit::Ok);
+    /// }
+    /// ```
+    ///
+    /// The struct is only read-only if every field is, so this can not be run as a read-only system:
+    /// ```rust compile_fail
+    /// use axecs::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Resource)]
+    /// struct Config {
+    ///     volume : f32
+    /// }
+    ///
+    /// #[derive(Query)]
+    /// struct VolumeParams<'l> {
+    ///     config : Res<&'l mut Config>
+    /// }
+    ///
+    /// async fn mute(mut params : VolumeParams<'_>) {
+    ///     params.config.volume = 0.0;
+    /// }
+    ///
+    /// let world = Arc::new(World::new());
+    /// world.system(mute);
+    /// ```
+    #[cfg(feature = "derive")]
+    #[doc(cfg(feature = "derive"))]
+    pub use axecs_macro::Query;
+
     #[doc(inline)]
-    pub use crate::query::Scoped;
+    pub use crate::query::{ Scoped, Local };
     #[doc(inline)]
-    #[cfg(not(feature = "no_std"))]
     pub use crate::query::{ EventReader, EventWriter };
+    #[doc(inline)]
+    #[cfg(not(feature = "no_std"))]
+    pub use crate::world::Trigger;
 
     #[doc(inline)]
     pub use crate::system::{ IntoSystem, IntoSystemPassable, IntoUnitSystem, In };
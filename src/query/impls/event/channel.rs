@@ -0,0 +1,738 @@
+//! Events sent to a channel for each [`EventReader`].
+
+
+use crate::world::World;
+use crate::entity::Entity;
+use crate::resource::Resource;
+use crate::system::SystemId;
+use crate::query::{ Query, QueryAcquireResult, QueryValidator };
+use super::{ Event, EventConfig, EventPolicy, DroppedEvents, try_event_queue };
+use core::task::{ Context, Poll, Waker };
+use core::pin::Pin;
+use core::future::poll_fn;
+use core::sync::atomic::{ AtomicU64, Ordering };
+use core::marker::PhantomData;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::collections::VecDeque;
+use std::sync::Mutex;
+use async_std::channel::{ self, Sender, Receiver, RecvError, TryRecvError, TrySendError };
+use async_std::stream::Stream;
+use async_std::sync::RwLock;
+
+
+/// The channel of a single [`EventReader`], as held by an [`EventQueue`].
+struct EventChannel<E : Event> {
+
+    /// The sending half of the channel.
+    tx : Sender<(u64, E)>,
+
+    /// A receiving half of the channel, used to drop the oldest events when it is full.
+    rx : Receiver<(u64, E)>
+
+}
+
+impl<E : Event> Clone for EventChannel<E> {
+    fn clone(&self) -> Self { Self {
+        tx : self.tx.clone(),
+        rx : self.rx.clone()
+    } }
+}
+
+
+/// TODO: Doc comment
+pub struct EventQueue<E : Event> {
+
+    /// TODO: Doc comment
+    events  : Arc<RwLock<Vec<EventChannel<E>>>>,
+
+    /// The shared buffer of events, if this event type is buffered. See [`App::add_buffered_event`](crate::app::App::add_buffered_event).
+    buffer  : Option<Arc<EventBuffer<E>>>,
+
+    /// The capacity and policy of each reader's channel, if they are bounded. See [`App::add_event`](crate::app::App::add_event).
+    config  : Option<EventConfig>,
+
+    /// The number of events dropped because a reader was full.
+    dropped : Arc<AtomicU64>
+
+}
+
+unsafe impl<E : Event> Sync for EventQueue<E> { }
+unsafe impl<E : Event> Send for EventQueue<E> { }
+
+impl<E : Event> Resource for EventQueue<E> { }
+
+impl<E : Event> EventQueue<E> {
+
+    /// Creates an [`EventQueue`] which gives each [`EventReader`] its own channel.
+    pub(crate) fn new() -> Self { Self {
+        events  : Arc::new(RwLock::new(Vec::new())),
+        buffer  : None,
+        config  : None,
+        dropped : Arc::new(AtomicU64::new(0))
+    } }
+
+    /// Creates an [`EventQueue`] which gives each [`EventReader`] its own channel, bounded as described by `config`.
+    pub(crate) fn new_bounded(config : EventConfig) -> Self { Self {
+        config : Some(config),
+        ..Self::new()
+    } }
+
+    /// Creates an [`EventQueue`] which keeps events in a shared buffer for some number of world cycles.
+    pub(crate) fn new_buffered(cycles : u64) -> Self { Self {
+        buffer : Some(Arc::new(EventBuffer::new(cycles))),
+        ..Self::new()
+    } }
+
+    /// Returns another handle to the same queue.
+    pub(super) fn share(&self) -> Self { Self {
+        events  : Arc::clone(&self.events),
+        buffer  : self.buffer.as_ref().map(Arc::clone),
+        config  : self.config,
+        dropped : Arc::clone(&self.dropped)
+    } }
+
+    /// Returns a [`DroppedEvents`] resource which counts the events dropped by this queue.
+    pub(crate) fn dropped_events(&self) -> DroppedEvents<E> { DroppedEvents {
+        dropped : Arc::clone(&self.dropped),
+        marker  : PhantomData
+    } }
+
+    /// Waits until no other handle is registering or deregistering a reader.
+    pub(super) async fn wait_unlocked(&self) {
+        drop(self.events.write().await);
+    }
+
+    /// Sends an event to every reader, tagged with world cycle `cycle`.
+    pub(super) async fn send(&self, cycle : u64, event : E) {
+        if let Some(buffer) = &self.buffer {
+            buffer.push(cycle, [event]);
+            return;
+        }
+        let mut disconnected = false;
+        for channel in &self.channels().await {
+            disconnected |= ! self.send_to(channel, cycle, event.clone()).await;
+        }
+        if (disconnected) { self.prune().await; }
+    }
+
+    /// Sends several events to every reader, tagged with world cycle `cycle`.
+    pub(super) async fn send_batch<I : IntoIterator<Item = E> + Clone>(&self, cycle : u64, events : I) {
+        if let Some(buffer) = &self.buffer {
+            buffer.push(cycle, events);
+            return;
+        }
+        let mut disconnected = false;
+        for channel in &self.channels().await {
+            for event in events.clone().into_iter() {
+                disconnected |= ! self.send_to(channel, cycle, event).await;
+            }
+        }
+        if (disconnected) { self.prune().await; }
+    }
+
+    /// Returns handles to the channel of every reader.
+    ///
+    /// These are copied out so that the senders are not locked while waiting for a full reader, which would stop readers from registering or deregistering.
+    async fn channels(&self) -> Vec<EventChannel<E>> {
+        self.events.read().await.clone()
+    }
+
+    /// Sends an event to a single reader's channel, following this queue's [`EventPolicy`] if the channel is full.
+    ///
+    /// Returns `false` if the reader no longer exists.
+    async fn send_to(&self, channel : &EventChannel<E>, cycle : u64, event : E) -> bool {
+        let mut event = (cycle, event);
+        loop {
+            match (channel.tx.try_send(event)) {
+                Ok(())                         => { return true; },
+                Err(TrySendError::Closed(_))   => { return false; },
+                Err(TrySendError::Full(full))  => { event = full; }
+            }
+            match (self.config.map(|config| config.policy)) {
+                Some(EventPolicy::DropOldest) => {
+                    if (channel.rx.try_recv().is_ok()) {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                },
+                Some(EventPolicy::DropNewest) | None => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return true;
+                },
+                Some(EventPolicy::Block) => {
+                    return channel.tx.send(event).await.is_ok();
+                }
+            }
+        }
+    }
+
+    /// Removes the senders of any [`EventReader`]s which no longer exist.
+    async fn prune(&self) {
+        self.events.write().await.retain(|channel| ! channel.tx.is_closed());
+    }
+
+}
+
+
+/// A buffer of events shared by every [`EventReader`] of a buffered event type.
+///
+/// Each event is kept for a fixed number of world cycles after the one it was sent in. See [`World::cycle`].
+struct EventBuffer<E : Event> {
+
+    /// The number of world cycles that each event is kept for.
+    cycles : u64,
+
+    /// The buffered events, and the wakers of readers waiting for new ones.
+    inner  : Mutex<EventBufferInner<E>>
+
+}
+
+/// The contents of an [`EventBuffer`].
+struct EventBufferInner<E : Event> {
+
+    /// The buffered events, and the world cycles that they were sent in, from oldest to newest.
+    events   : VecDeque<(u64, E)>,
+
+    /// The ID of the oldest buffered event. IDs increase by one with each event sent.
+    first_id : u64,
+
+    /// The wakers of readers waiting for new events.
+    wakers   : Vec<Waker>
+
+}
+
+impl<E : Event> EventBuffer<E> {
+
+    /// Creates an empty [`EventBuffer`] which keeps events for `cycles` world cycles.
+    fn new(cycles : u64) -> Self { Self {
+        cycles,
+        inner  : Mutex::new(EventBufferInner {
+            events   : VecDeque::new(),
+            first_id : 0,
+            wakers   : Vec::new()
+        })
+    } }
+
+    /// Locks the contents of this buffer, dropping any events which have expired as of world cycle `cycle`.
+    fn lock(&self, cycle : u64) -> std::sync::MutexGuard<'_, EventBufferInner<E>> {
+        let mut inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+        while let Some((sent, _)) = inner.events.front() && (sent + self.cycles <= cycle) {
+            inner.events.pop_front();
+            inner.first_id += 1;
+        }
+        inner
+    }
+
+    /// Adds some events to this buffer as sent in world cycle `cycle`, waking any waiting readers.
+    fn push<I : IntoIterator<Item = E>>(&self, cycle : u64, events : I) {
+        let mut inner = self.lock(cycle);
+        inner.events.extend(events.into_iter().map(|event| (cycle, event)));
+        for waker in inner.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Returns the next event after `cursor` that hasn't expired as of world cycle `cycle`, and the world cycle it was sent in, advancing `cursor` past it.
+    ///
+    /// If there are none, `waker` is woken once another event is sent.
+    fn poll_read(&self, cycle : u64, cursor : &AtomicU64, waker : Option<&Waker>) -> Poll<(u64, E)> {
+        let mut inner = self.lock(cycle);
+        let     id    = cursor.load(Ordering::Relaxed).max(inner.first_id);
+        match (inner.events.get((id - inner.first_id) as usize)) {
+            Some((sent, event)) => {
+                let event = (*sent, event.clone());
+                cursor.store(id + 1, Ordering::Relaxed);
+                Poll::Ready(event)
+            },
+            None => {
+                if let Some(waker) = waker && (! inner.wakers.iter().any(|other| other.will_wake(waker))) {
+                    inner.wakers.push(waker.clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Returns the number of events after `cursor` that haven't expired as of world cycle `cycle`.
+    fn len(&self, cycle : u64, cursor : &AtomicU64) -> usize {
+        let inner = self.lock(cycle);
+        let id    = cursor.load(Ordering::Relaxed).max(inner.first_id);
+        (inner.first_id + (inner.events.len() as u64)).saturating_sub(id) as usize
+    }
+
+    /// Returns the ID that the next event sent will have.
+    fn next_id(&self, cycle : u64) -> u64 {
+        let inner = self.lock(cycle);
+        inner.first_id + (inner.events.len() as u64)
+    }
+
+}
+
+
+/// TODO: Doc comment
+pub struct EventWriter<E : Event> {
+
+    /// The world to trigger events in.
+    world : Arc<World>,
+
+    /// The queue to send events to.
+    queue : EventQueue<E>,
+
+    /// The world cycle that this writer was acquired in.
+    cycle : u64
+
+}
+
+impl<E : Event> Clone for EventWriter<E> {
+    fn clone(&self) -> Self { Self {
+        world : Arc::clone(&self.world),
+        queue : self.queue.share(),
+        cycle : self.cycle
+    } }
+}
+
+unsafe impl<E : Event> Sync for EventWriter<E> { }
+unsafe impl<E : Event> Send for EventWriter<E> { }
+
+unsafe impl<E : Event + 'static> Query for EventWriter<E> {
+    type Item  = EventWriter<E>;
+    /// The [`EventQueue`], if it has been found yet.
+    type State = Option<EventQueue<E>>;
+
+    fn init_state(world : Arc<World>, _system_id : Option<SystemId>) -> Self::State {
+        match (try_event_queue::<E>(&world)) {
+            Poll::Ready(queue) => Some(queue),
+            Poll::Pending      => None
+        }
+    }
+
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        if (state.is_none()) {
+            let Poll::Ready(queue) = try_event_queue::<E>(&world) else { return Poll::Pending; };
+            *state = Some(queue);
+        }
+        // SAFETY: `state` was set above.
+        let queue = unsafe{ state.as_ref().unwrap_unchecked() };
+        Poll::Ready(QueryAcquireResult::Ready(EventWriter { queue : queue.share(), cycle : world.cycle(), world }))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::empty()
+    }
+}
+
+impl<E : Event> EventWriter<E> {
+
+    /// TODO: Doc comment
+    ///
+    /// If events of type `E` were added with [`EventPolicy::Block`], this waits until every reader has room for the event.
+    /// A full reader only makes room when it is read, so this never completes if the reader is held by the sending system,
+    ///  or is only read by systems which can not run until the sending system finishes.
+    pub async fn send(&self, event : E) {
+        self.queue.send(self.cycle, event).await
+    }
+
+    /// TODO: Doc comment
+    pub async fn send_batch<I : IntoIterator<Item = E> + Clone>(&self, events : I) {
+        self.queue.send_batch(self.cycle, events).await
+    }
+
+    /// Triggers an event on an entity, running the entity's observer systems for events of type `E`.
+    ///
+    /// The observers run once the current systems have finished. Triggered events are not sent to [`EventReader`]s.
+    /// See [`Trigger`](crate::world::Trigger).
+    pub async fn trigger(&self, entity : Entity, event : E)
+    where E : Send + Sync + 'static
+    {
+        self.world.deferred_cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.trigger(entity, event).await })
+        ))
+    }
+
+    /// Returns the world cycle that this writer was acquired in, which events sent by it are tagged with.
+    ///
+    /// See [`World::cycle`] and [`EventReader::try_read_with_cycle`].
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+}
+
+
+/// TODO: Doc comment
+pub struct EventReader<E : Event> {
+
+    /// TODO: Doc comment
+    events   : Receiver<(u64, E)>,
+
+    /// The shared buffer and this reader's cursor in it, if this event type is buffered.
+    buffered : Option<(Arc<EventBuffer<E>>, Arc<AtomicU64>)>,
+
+    /// The world cycle that this reader was acquired in.
+    cycle    : u64
+
+}
+
+unsafe impl<E : Event> Sync for EventReader<E> { }
+unsafe impl<E : Event> Send for EventReader<E> { }
+
+/// The state of an [`EventReader`] query.
+pub struct EventReaderState<E : Event> {
+
+    /// The receiving half of this reader's channel.
+    rx       : Receiver<(u64, E)>,
+
+    /// The sending half of this reader's channel, if it has not been registered with the [`EventQueue`] yet.
+    tx       : Option<Sender<(u64, E)>>,
+
+    /// The [`EventQueue`] that this reader was registered with, if it has been.
+    queue    : Option<EventQueue<E>>,
+
+    /// The shared buffer and the ID of the next event to read from it, if this event type is buffered.
+    buffered : Option<(Arc<EventBuffer<E>>, Arc<AtomicU64>)>
+
+}
+
+unsafe impl<E : Event + 'static> Query for EventReader<E> {
+    type Item  = EventReader<E>;
+    type State = EventReaderState<E>;
+
+    fn init_state(world : Arc<World>, _system_id : Option<SystemId>) -> Self::State {
+        let mut state = EventReaderState::new();
+        let _ = Self::try_register(&world, &mut state);
+        state
+    }
+
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        if (Self::try_register(&world, state).is_pending()) {
+            return Poll::Pending;
+        }
+        Poll::Ready(QueryAcquireResult::Ready(EventReader::from_state(state, world.cycle())))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::empty()
+    }
+}
+
+impl<E : Event> EventReaderState<E> {
+
+    /// Creates the state of a reader which has not been registered with the [`EventQueue`] yet.
+    pub(super) fn new() -> Self {
+        let (tx, rx) = channel::unbounded();
+        Self { rx, tx : Some(tx), queue : None, buffered : None }
+    }
+
+}
+
+impl<E : Event> Drop for EventReaderState<E> {
+    fn drop(&mut self) {
+        // Closing the channel lets `EventWriter` prune the sender if it can't be removed here.
+        self.rx.close();
+        if let Some(queue) = &self.queue && let Some(mut senders) = queue.events.try_write() {
+            senders.retain(|channel| ! channel.tx.is_closed());
+        }
+    }
+}
+
+impl<E : Event + 'static> EventReader<E> {
+
+    /// Attempts to give the sending half of a reader's channel to the [`EventQueue`], if it hasn't been already.
+    ///
+    /// If the event type is bounded, the reader's channel is first replaced with a bounded one.
+    /// If the event type is buffered, the reader starts at the oldest event still in the buffer instead.
+    fn try_register(world : &World, state : &mut EventReaderState<E>) -> Poll<()> {
+        if (state.tx.is_none()) { return Poll::Ready(()); }
+        let Poll::Ready(queue) = try_event_queue::<E>(world) else { return Poll::Pending; };
+        Self::try_register_with(queue, state)
+    }
+
+    /// Attempts to give the sending half of a reader's channel to `queue`, if it hasn't been already.
+    ///
+    /// See [`EventReader::try_register`].
+    pub(super) fn try_register_with(queue : EventQueue<E>, state : &mut EventReaderState<E>) -> Poll<()> {
+        if (state.tx.is_none()) { return Poll::Ready(()); }
+        if let Some(buffer) = &queue.buffer {
+            state.tx       = None;
+            state.buffered = Some((Arc::clone(buffer), Arc::new(AtomicU64::new(0))));
+            state.queue    = Some(queue);
+            return Poll::Ready(());
+        }
+        let Some(mut events) = queue.events.try_write() else { return Poll::Pending; };
+        if let Some(config) = queue.config {
+            // Nothing has been sent on the unregistered channel yet, so it can be safely replaced.
+            let (tx, rx) = channel::bounded(config.capacity);
+            state.tx = Some(tx);
+            state.rx = rx;
+        }
+        if let Some(tx) = state.tx.take() {
+            events.push(EventChannel { tx, rx : state.rx.clone() });
+        }
+        drop(events);
+        state.queue = Some(queue);
+        Poll::Ready(())
+    }
+
+    /// Creates a reader from the state of a registered reader, acquired in world cycle `cycle`.
+    pub(super) fn from_state(state : &EventReaderState<E>, cycle : u64) -> Self { Self {
+        events   : state.rx.clone(),
+        buffered : state.buffered.as_ref().map(|(buffer, cursor)| (Arc::clone(buffer), Arc::clone(cursor))),
+        cycle
+    } }
+
+}
+
+impl<E : Event> EventReader<E> {
+    /// Sets the world cycle that this reader considers to be the current one.
+    pub(super) fn set_cycle(&mut self, cycle : u64) {
+        self.cycle = cycle;
+    }
+
+
+    /// Waits for the next event, without blocking the thread.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Event, Clone)]
+    /// struct Damage(u32);
+    ///
+    /// // Waits for the next three hits, so this system finishes once they have arrived.
+    /// async fn log_next_hits(
+    ///     damage : EventReader<Damage>
+    /// ) {
+    ///     for _ in 0..3 {
+    ///         let Ok(Damage(amount)) = damage.recv().await else { break; };
+    ///         println!("Took {} damage", amount);
+    ///     }
+    /// }
+    /// ```
+    pub async fn recv(&self) -> Result<E, RecvError> {
+        match (&self.buffered) {
+            Some((buffer, cursor)) => Ok(poll_fn(|ctx| buffer.poll_read(self.cycle, cursor, Some(ctx.waker()))).await.1),
+            None                   => self.events.recv().await.map(|(_, event)| event)
+        }
+    }
+
+    /// TODO: Doc comment
+    pub fn try_read(&self) -> Result<E, TryRecvError> {
+        self.try_read_with_cycle().map(|(_, event)| event)
+    }
+
+    /// Returns the next event if there is one, along with the world cycle that it was sent in. See [`World::cycle`].
+    pub fn try_read_with_cycle(&self) -> Result<(u64, E), TryRecvError> {
+        match (&self.buffered) {
+            Some((buffer, cursor)) => match (buffer.poll_read(self.cycle, cursor, None)) {
+                Poll::Ready(event) => Ok(event),
+                Poll::Pending      => Err(TryRecvError::Empty)
+            },
+            None => self.events.try_recv()
+        }
+    }
+
+    /// Returns the number of events waiting to be read by this reader.
+    pub fn len(&self) -> usize {
+        match (&self.buffered) {
+            Some((buffer, cursor)) => buffer.len(self.cycle, cursor),
+            None                   => self.events.len()
+        }
+    }
+
+    /// Returns `true` if there are no events waiting to be read by this reader.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Marks every event waiting to be read by this reader as read.
+    pub fn clear(&self) {
+        match (&self.buffered) {
+            Some((buffer, cursor)) => { cursor.store(buffer.next_id(self.cycle), Ordering::Relaxed); },
+            None                   => { while (self.events.try_recv().is_ok()) { } }
+        }
+    }
+
+}
+
+impl<E : Event> Iterator for EventReader<E> {
+    type Item = E;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_read().ok()
+    }
+}
+
+impl<E : Event> Stream for EventReader<E> {
+    type Item = E;
+    fn poll_next(mut self : Pin<&mut Self>, ctx : &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match (&self.buffered) {
+            Some((buffer, cursor)) => buffer.poll_read(self.cycle, cursor, Some(ctx.waker())).map(|(_, event)| Some(event)),
+            None                   => Pin::new(&mut self.events).poll_next(ctx).map(|event| event.map(|(_, event)| event))
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+
+    #[derive(Clone)]
+    struct Ping;
+    impl Event for Ping { }
+
+    impl Event for usize { }
+
+    fn sender_count(world : &World) -> usize {
+        let Poll::Ready(queue) = try_event_queue::<Ping>(world) else { panic!("Event queue is locked"); };
+        block_on(queue.events.read()).len()
+    }
+
+    #[test]
+    fn dropped_readers_deregister() { block_on(async {
+        let world = Arc::new(World::new());
+        let mut writer = world.query_mut::<EventWriter<Ping>>();
+
+        // Readers remove their own senders when dropped.
+        for _ in 0..1000 {
+            let mut reader = world.system_mut(async |_events : EventReader<Ping>| { });
+            reader.run().await;
+            writer.acquire().await.send(Ping).await;
+        }
+        assert_eq!(sender_count(&world), 0);
+
+        // Readers dropped while the senders are locked are pruned by the next send.
+        let mut reader = world.query_mut::<EventReader<Ping>>();
+        let mut kept   = world.query_mut::<EventReader<Ping>>();
+        assert_eq!(sender_count(&world), 2);
+        let Poll::Ready(queue) = try_event_queue::<Ping>(&world) else { panic!("Event queue is locked"); };
+        let guard = queue.events.read().await;
+        drop(reader.acquire().await);
+        drop(reader);
+        drop(guard);
+        assert_eq!(sender_count(&world), 2);
+        writer.acquire().await.send(Ping).await;
+        assert_eq!(sender_count(&world), 1);
+        assert!(kept.acquire().await.try_read().is_ok());
+    }); }
+
+    #[test]
+    fn full_readers_follow_policy() { block_on(async {
+        for (policy, first) in [(EventPolicy::DropOldest, 2), (EventPolicy::DropNewest, 0)] {
+            let world = Arc::new(World::new());
+            let queue = EventQueue::<usize>::new_bounded(EventConfig { capacity : 3, policy });
+            let dropped = queue.dropped_events();
+            world.insert_resource(queue).await;
+            let mut writer = world.query_mut::<EventWriter<usize>>();
+            let mut reader = world.query_mut::<EventReader<usize>>();
+            let reader = reader.acquire().await;
+            writer.acquire().await.send_batch(0..5).await;
+            assert_eq!(dropped.count(), 2);
+            assert_eq!(reader.collect::<Vec<_>>(), (first..(first + 3)).collect::<Vec<_>>());
+        }
+    }); }
+
+    #[test]
+    fn blocked_send_waits_for_reader() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(EventQueue::<usize>::new_bounded(EventConfig { capacity : 1, policy : EventPolicy::Block })).await;
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut reader = world.query_mut::<EventReader<usize>>();
+        let reader = reader.acquire().await;
+        let writer = writer.acquire().await;
+        writer.send(0).await;
+
+        // The reader is full, so the send can not complete until it is read.
+        let mut ctx  = Context::from_waker(Waker::noop());
+        let mut send = Box::pin(writer.send(1));
+        for _ in 0..10 {
+            assert!(send.as_mut().poll(&mut ctx).is_pending());
+        }
+
+        // Other readers can still register while the send is waiting.
+        let mut other = world.query_mut::<EventReader<usize>>();
+        drop(other.acquire().await);
+
+        assert_eq!(reader.try_read().ok(), Some(0));
+        assert!(send.as_mut().poll(&mut ctx).is_ready());
+        assert_eq!(reader.try_read().ok(), Some(1));
+    }); }
+
+    #[test]
+    fn recv_and_stream_wait_for_events() { block_on(async {
+        let world = Arc::new(World::new());
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut reader = world.query_mut::<EventReader<usize>>();
+        let writer     = writer.acquire().await;
+        let mut reader = reader.acquire().await;
+        let mut ctx    = Context::from_waker(Waker::noop());
+
+        let mut recv = Box::pin(reader.recv());
+        assert!(recv.as_mut().poll(&mut ctx).is_pending());
+        writer.send(0).await;
+        assert_eq!(recv.as_mut().poll(&mut ctx), Poll::Ready(Ok(0)));
+        drop(recv);
+
+        assert!(Pin::new(&mut reader).poll_next(&mut ctx).is_pending());
+        writer.send_batch(1..3).await;
+        assert_eq!(Pin::new(&mut reader).poll_next(&mut ctx), Poll::Ready(Some(1)));
+        assert_eq!(reader.recv().await, Ok(2));
+    }); }
+
+    #[test]
+    fn buffered_events_expire_after_cycles() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(EventQueue::<usize>::new_buffered(2)).await;
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut early  = world.query_mut::<EventReader<usize>>();
+        writer.acquire().await.send(0).await;
+        world.advance_cycle();
+        writer.acquire().await.send(1).await;
+
+        // Readers see events sent before they were created, if they are still buffered.
+        let mut late = world.query_mut::<EventReader<usize>>();
+        assert_eq!(late.acquire().await.collect::<Vec<_>>(), [0, 1]);
+        let reader = early.acquire().await;
+        assert_eq!(reader.try_read_with_cycle(), Ok((0, 0)));
+        assert_eq!(reader.try_read_with_cycle(), Ok((1, 1)));
+        drop(reader);
+
+        // Events are dropped once they have been kept for 2 cycles after the one they were sent in.
+        writer.acquire().await.send(2).await;
+        world.advance_cycle();
+        assert_eq!(world.query_mut::<EventReader<usize>>().acquire().await.collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(early.acquire().await.collect::<Vec<_>>(), [2]);
+        world.advance_cycle();
+        world.advance_cycle();
+        assert!(world.query_mut::<EventReader<usize>>().acquire().await.is_empty());
+
+        // Waiting readers are woken once an event is sent.
+        let reader   = late.acquire().await;
+        let mut ctx  = Context::from_waker(Waker::noop());
+        let mut recv = Box::pin(reader.recv());
+        assert!(recv.as_mut().poll(&mut ctx).is_pending());
+        writer.acquire().await.send(3).await;
+        assert_eq!(recv.as_mut().poll(&mut ctx), Poll::Ready(Ok(3)));
+    }); }
+
+    #[test]
+    fn app_events_are_bounded() { block_on(async {
+        use crate::app::App;
+        use crate::resource::ResourceStorage;
+
+        let mut app = App::new();
+        app.add_event::<Ping>(EventConfig { capacity : 2, policy : EventPolicy::DropNewest });
+        let sender   = app.event_sender::<Ping>();
+        let receiver = app.event_receiver::<Ping>();
+        let world    = Arc::new(World::new_with(ResourceStorage::new_with(app.take_resources())));
+
+        sender.send_batch([Ping, Ping, Ping]).await;
+        assert_eq!(receiver.len(), 2);
+        let dropped = world.resources().get_ref::<DroppedEvents<Ping>>().await.unwrap();
+        assert_eq!(dropped.count(), 1);
+        assert_eq!(dropped.reset(), 1);
+        assert_eq!(dropped.count(), 0);
+    }); }
+
+}
@@ -0,0 +1,423 @@
+//! Events, sent by [`EventWriter`]s and received by [`EventReader`]s.
+//!
+//! Normally each reader has its own channel. Under the `no_std` feature, every reader instead shares a single ring buffer,
+//!  built on the crate's own [`RwLock`](crate::util::rwlock::RwLock), with a cursor for each reader.
+
+
+#[cfg(not(feature = "no_std"))]
+mod channel;
+#[cfg(not(feature = "no_std"))]
+pub use channel::*;
+
+#[cfg(feature = "no_std")]
+mod ring;
+#[cfg(feature = "no_std")]
+pub use ring::*;
+
+
+use crate::world::World;
+use crate::resource::{ Resource, RawResourceStorage };
+use core::any::type_name;
+use core::task::Poll;
+#[cfg(not(feature = "no_std"))]
+use core::task::Context;
+#[cfg(not(feature = "no_std"))]
+use core::pin::Pin;
+use core::sync::atomic::{ AtomicU64, Ordering };
+use core::marker::PhantomData;
+use alloc::sync::Arc;
+#[cfg(not(feature = "no_std"))]
+use async_std::channel::{ RecvError, TryRecvError };
+#[cfg(not(feature = "no_std"))]
+use async_std::stream::Stream;
+
+
+/// TODO: Doc comment
+pub trait Event : Clone { }
+
+
+/// How many events each [`EventReader`] can hold, and what happens when one is full. See [`App::add_event`](crate::app::App::add_event).
+#[derive(Clone, Copy, Debug)]
+pub struct EventConfig {
+
+    /// The maximum number of unread events that each [`EventReader`] can hold. Must not be zero.
+    pub capacity : usize,
+
+    /// What happens when an event is sent to a reader which is already holding `capacity` events.
+    pub policy   : EventPolicy
+
+}
+
+/// What happens when an event is sent to an [`EventReader`] which is full. See [`EventConfig`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventPolicy {
+
+    /// The oldest unread event is dropped to make room for the new one.
+    DropOldest,
+
+    /// The new event is dropped.
+    DropNewest,
+
+    /// [`EventWriter::send`] waits until the reader has room.
+    ///
+    /// Readers only make room when they are read, so a send to a full reader never completes if that reader is held by the
+    ///  sending system, or is only read by systems which can not run until the sending system finishes.
+    Block
+
+}
+
+
+/// The number of events of type `E` that have been dropped because an [`EventReader`] was full.
+///
+/// This resource is inserted by [`App::add_event`](crate::app::App::add_event). See [`EventPolicy`].
+pub struct DroppedEvents<E : Event> {
+
+    /// The counter, shared with the [`EventQueue`].
+    dropped : Arc<AtomicU64>,
+
+    /// [`PhantomData`] on the event type.
+    marker  : PhantomData<fn() -> E>
+
+}
+
+impl<E : Event> Resource for DroppedEvents<E> { }
+
+impl<E : Event> DroppedEvents<E> {
+
+    /// Returns the number of events dropped so far.
+    ///
+    /// An event dropped for several readers is counted once for each of them.
+    pub fn count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Resets the counter to zero, returning the number of events dropped before it was reset.
+    pub fn reset(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+
+}
+
+
+/// A handle for sending events of type `E` from outside of the world, such as from another thread or task.
+///
+/// Events are sent to the same readers as those of [`EventWriter`]s, and are tagged with the world's current cycle. See [`World::cycle`].
+/// Created by [`World::event_sender`] or [`App::event_sender`](crate::app::App::event_sender), and can be cloned freely.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use axecs::query::EventSender;
+///
+/// #[derive(Event, Clone)]
+/// struct KeyPress {
+///     key : char
+/// }
+///
+/// async fn listen_for_keys(keys : EventSender<KeyPress>) {
+///     for key in "hello".chars() {
+///         keys.send(KeyPress { key }).await;
+///     }
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugin(CycleSchedulerPlugin);
+/// let keys = app.event_sender::<KeyPress>();
+/// std::thread::spawn(move || async_std::task::block_on(listen_for_keys(keys)));
+/// ```
+pub struct EventSender<E : Event> {
+
+    /// The queue to send events to.
+    queue : EventQueue<E>,
+
+    /// The number of cycles that have been completed in the world.
+    cycle : Arc<AtomicU64>
+
+}
+
+impl<E : Event> Clone for EventSender<E> {
+    fn clone(&self) -> Self { Self {
+        queue : self.queue.share(),
+        cycle : Arc::clone(&self.cycle)
+    } }
+}
+
+impl<E : Event + 'static> EventSender<E> {
+
+    /// Creates an [`EventSender`] for the [`EventQueue`] of a [`World`], creating the queue if needed.
+    pub(crate) async fn from_world(world : &World) -> Self { Self {
+        queue : event_queue::<E>(world).await,
+        cycle : world.resources().cycle_handle()
+    } }
+
+    /// Creates an [`EventSender`] for the [`EventQueue`] in some resources, creating the queue if needed.
+    pub(crate) fn from_raw(raw : &mut RawResourceStorage) -> Self { Self {
+        queue : raw_event_queue::<E>(raw),
+        cycle : raw.cycle_handle()
+    } }
+
+}
+
+impl<E : Event> EventSender<E> {
+
+    /// Sends an event to every [`EventReader`] and [`EventReceiver`] of type `E`.
+    ///
+    /// If events of type `E` were added with [`EventPolicy::Block`], this waits until every reader has room for the event.
+    pub async fn send(&self, event : E) {
+        self.queue.send(self.cycle.load(Ordering::Relaxed), event).await
+    }
+
+    /// Sends several events to every [`EventReader`] and [`EventReceiver`] of type `E`.
+    pub async fn send_batch<I : IntoIterator<Item = E> + Clone>(&self, events : I) {
+        self.queue.send_batch(self.cycle.load(Ordering::Relaxed), events).await
+    }
+
+}
+
+
+/// A handle for receiving events of type `E` from outside of the world, such as from another thread or task.
+///
+/// This behaves like an [`EventReader`] which lives as long as the handle does,
+///  so it only receives events sent after it was created unless events of type `E` are buffered.
+/// Created by [`World::event_receiver`] or [`App::event_receiver`](crate::app::App::event_receiver).
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use axecs::query::EventReceiver;
+///
+/// #[derive(Event, Clone)]
+/// struct Chat {
+///     message : String
+/// }
+///
+/// async fn forward_chat(mut chat : EventReceiver<Chat>) {
+///     while let Ok(Chat { message }) = chat.recv().await {
+///         println!("Sending {:?}", message);
+///     }
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugin(CycleSchedulerPlugin);
+/// let chat = app.event_receiver::<Chat>();
+/// std::thread::spawn(move || async_std::task::block_on(forward_chat(chat)));
+/// ```
+///
+/// A receiver can only be sent to another thread if its events can be:
+/// ```rust compile_fail
+/// use axecs::prelude::*;
+/// use std::rc::Rc;
+///
+/// #[derive(Event, Clone)]
+/// struct Chat {
+///     message : Rc<str>
+/// }
+///
+/// let mut app = App::new();
+/// let chat = app.event_receiver::<Chat>();
+/// std::thread::spawn(move || drop(chat));
+/// ```
+pub struct EventReceiver<E : Event> {
+
+    /// The state of the reader, which keeps it registered with the queue until this is dropped.
+    _state : EventReaderState<E>,
+
+    /// The reader that events are received through.
+    reader : EventReader<E>,
+
+    /// The number of cycles that have been completed in the world.
+    cycle  : Arc<AtomicU64>
+
+}
+
+// SAFETY: Received events are moved out of the queue into the thread holding the receiver.
+unsafe impl<E : Event + Send + Sync> Sync for EventReceiver<E> { }
+unsafe impl<E : Event + Send> Send for EventReceiver<E> { }
+
+impl<E : Event + 'static> EventReceiver<E> {
+
+    /// Attempts to create an [`EventReceiver`] registered with `queue`.
+    ///
+    /// This never blocks. If the queue is locked, [`Poll::Pending`] is returned.
+    fn try_new(queue : &EventQueue<E>, cycle : &Arc<AtomicU64>) -> Poll<Self> {
+        let mut state = EventReaderState::new();
+        if (EventReader::try_register_with(queue.share(), &mut state).is_pending()) {
+            return Poll::Pending;
+        }
+        let reader = EventReader::from_state(&state, cycle.load(Ordering::Relaxed));
+        Poll::Ready(Self { _state : state, reader, cycle : Arc::clone(cycle) })
+    }
+
+    /// Creates an [`EventReceiver`] for the [`EventQueue`] of a [`World`], creating the queue if needed.
+    pub(crate) async fn from_world(world : &World) -> Self {
+        let queue = event_queue::<E>(world).await;
+        let cycle = world.resources().cycle_handle();
+        loop {
+            if let Poll::Ready(receiver) = Self::try_new(&queue, &cycle) {
+                return receiver;
+            }
+            queue.wait_unlocked().await;
+        }
+    }
+
+    /// Creates an [`EventReceiver`] for the [`EventQueue`] in some resources, creating the queue if needed.
+    ///
+    /// ### Panics
+    /// Panics if the queue is locked.
+    #[track_caller]
+    pub(crate) fn from_raw(raw : &mut RawResourceStorage) -> Self {
+        match (Self::try_new(&raw_event_queue::<E>(raw), &raw.cycle_handle())) {
+            Poll::Ready(receiver) => receiver,
+            Poll::Pending         => panic!("Event queue {} is locked", type_name::<E>())
+        }
+    }
+
+}
+
+impl<E : Event> EventReceiver<E> {
+
+    /// Brings the reader up to date with the world's current cycle.
+    fn refresh(&mut self) {
+        self.reader.set_cycle(self.cycle.load(Ordering::Relaxed));
+    }
+
+    /// Waits for the next event, without blocking the thread.
+    pub async fn recv(&mut self) -> Result<E, RecvError> {
+        self.refresh();
+        self.reader.recv().await
+    }
+
+    /// Returns the next event if there is one.
+    pub fn try_read(&mut self) -> Result<E, TryRecvError> {
+        self.refresh();
+        self.reader.try_read()
+    }
+
+    /// Returns the next event if there is one, along with the world cycle that it was sent in. See [`World::cycle`].
+    pub fn try_read_with_cycle(&mut self) -> Result<(u64, E), TryRecvError> {
+        self.refresh();
+        self.reader.try_read_with_cycle()
+    }
+
+    /// Returns the number of events waiting to be received.
+    pub fn len(&self) -> usize {
+        self.reader.len()
+    }
+
+    /// Returns `true` if there are no events waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.reader.is_empty()
+    }
+
+    /// Marks every event waiting to be received as received.
+    pub fn clear(&mut self) {
+        self.refresh();
+        self.reader.clear()
+    }
+
+}
+
+impl<E : Event> Iterator for EventReceiver<E> {
+    type Item = E;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_read().ok()
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<E : Event> Stream for EventReceiver<E> {
+    type Item = E;
+    fn poll_next(mut self : Pin<&mut Self>, ctx : &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.refresh();
+        Pin::new(&mut self.reader).poll_next(ctx)
+    }
+}
+
+
+/// Attempts to get the [`EventQueue`] for `E`, creating it if needed.
+///
+/// This never blocks. If the [`World`]'s resources or the queue are locked, [`Poll::Pending`] is returned.
+fn try_event_queue<E : Event + 'static>(world : &World) -> Poll<EventQueue<E>> {
+    let Poll::Ready(mut raw) = world.resources().try_write_raw() else { return Poll::Pending; };
+    try_raw_event_queue(&mut raw)
+}
+
+/// Gets the [`EventQueue`] for `E`, creating it if needed.
+async fn event_queue<E : Event + 'static>(world : &World) -> EventQueue<E> {
+    if let Some(queue) = world.resources().get_ref::<EventQueue<E>>().await {
+        return queue.share();
+    }
+    world.resources().get_mut_or_insert(EventQueue::new).await.share()
+}
+
+/// Gets the [`EventQueue`] for `E` in some resources, creating it if needed.
+///
+/// ### Panics
+/// Panics if the queue is locked.
+#[track_caller]
+fn raw_event_queue<E : Event + 'static>(raw : &mut RawResourceStorage) -> EventQueue<E> {
+    match (try_raw_event_queue(raw)) {
+        Poll::Ready(queue) => queue,
+        Poll::Pending      => panic!("Event queue {} is locked", type_name::<E>())
+    }
+}
+
+/// Attempts to get the [`EventQueue`] for `E` in some resources, creating it if needed.
+///
+/// This never blocks. If the queue is locked, [`Poll::Pending`] is returned.
+fn try_raw_event_queue<E : Event + 'static>(raw : &mut RawResourceStorage) -> Poll<EventQueue<E>> {
+    if let Some(lock) = raw.get::<EventQueue<E>, ()>() {
+        let Poll::Ready(cell) = lock.try_read() else { return Poll::Pending; };
+        // SAFETY: The cell was stored under the `TypeId` of `EventQueue<E>`.
+        Poll::Ready(unsafe{ cell.get_ref::<EventQueue<E>>() }.share())
+    } else {
+        let queue = EventQueue::new();
+        raw.insert(queue.share());
+        Poll::Ready(queue)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::ResourceStorage;
+    use alloc::vec::Vec;
+    use async_std::task::block_on;
+
+    #[derive(Clone)]
+    struct Ping(usize);
+    impl Event for Ping { }
+
+    #[test]
+    fn sender_and_receiver_share_queue() { block_on(async {
+        let world = Arc::new(World::new());
+        let mut receiver = world.event_receiver::<Ping>().await;
+        let mut reader   = world.query_mut::<EventReader<Ping>>();
+        let reader = reader.acquire().await;
+        let sender = world.event_sender::<Ping>().await;
+        sender.clone().send_batch([Ping(0), Ping(1)]).await;
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.by_ref().map(|Ping(i)| i).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(reader.map(|Ping(i)| i).collect::<Vec<_>>(), [0, 1]);
+        drop(receiver);
+        sender.send(Ping(2)).await;
+    }) }
+
+    #[test]
+    fn sender_tags_events_with_world_cycle() { block_on(async {
+        let mut raw  = RawResourceStorage::new();
+        let early    = EventSender::<Ping>::from_raw(&mut raw);
+        let world    = Arc::new(World::new_with(ResourceStorage::new_with(raw)));
+        let late     = world.event_sender::<Ping>().await;
+        let mut receiver = world.event_receiver::<Ping>().await;
+        world.advance_cycle();
+        world.advance_cycle();
+        early.send(Ping(0)).await;
+        late.send(Ping(1)).await;
+        assert_eq!(receiver.try_read_with_cycle().map(|(cycle, Ping(i))| (cycle, i)), Ok((2, 0)));
+        assert_eq!(receiver.try_read_with_cycle().map(|(cycle, Ping(i))| (cycle, i)), Ok((2, 1)));
+    }) }
+
+}
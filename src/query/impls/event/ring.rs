@@ -0,0 +1,644 @@
+//! Events kept in a ring buffer shared by every [`EventReader`], for use without `std`.
+
+
+use crate::world::World;
+use crate::resource::Resource;
+use crate::system::SystemId;
+use crate::query::{ Query, QueryAcquireResult, QueryValidator };
+use crate::util::rwlock::RwLock;
+use super::{ Event, EventConfig, EventPolicy, DroppedEvents, try_event_queue };
+use crate::util::wakers::WakerList;
+use core::task::Poll;
+use core::future::poll_fn;
+use core::sync::atomic::{ AtomicU64, Ordering };
+use core::marker::PhantomData;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::collections::VecDeque;
+
+
+/// The cursor value of an [`EventReader`] which no longer exists.
+const CLOSED : u64 = u64::MAX;
+
+
+/// The error returned by [`EventReader::try_read`] when there are no events waiting to be read.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TryRecvError;
+
+/// The error returned by [`EventReader::recv`]. Under the `no_std` feature, this is never returned.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RecvError;
+
+
+/// The events of a single type, and the [`EventReader`]s reading them.
+pub struct EventQueue<E : Event> {
+
+    /// The ring buffer of events, shared by every reader.
+    ring     : RwLock<EventRing<E>>,
+
+    /// The number of world cycles that each event is kept for, if this event type is buffered. See [`App::add_buffered_event`](crate::app::App::add_buffered_event).
+    cycles   : Option<u64>,
+
+    /// The capacity of the ring buffer and its policy when full, if it is bounded. See [`App::add_event`](crate::app::App::add_event).
+    config   : Option<EventConfig>,
+
+    /// The number of events dropped because a reader was full.
+    dropped  : Arc<AtomicU64>,
+
+    /// The wakers of readers waiting for new events.
+    readable : Arc<WakerList>,
+
+    /// The wakers of writers waiting for room in a full ring buffer.
+    writable : Arc<WakerList>
+
+}
+
+unsafe impl<E : Event> Sync for EventQueue<E> { }
+unsafe impl<E : Event> Send for EventQueue<E> { }
+
+impl<E : Event> Resource for EventQueue<E> { }
+
+impl<E : Event> EventQueue<E> {
+
+    /// Creates an [`EventQueue`] which keeps each event until every [`EventReader`] has read it.
+    pub(crate) fn new() -> Self { Self {
+        ring     : RwLock::new(EventRing::new(VecDeque::new())),
+        cycles   : None,
+        config   : None,
+        dropped  : Arc::new(AtomicU64::new(0)),
+        readable : Arc::new(WakerList::new()),
+        writable : Arc::new(WakerList::new())
+    } }
+
+    /// Creates an [`EventQueue`] which keeps each event until every [`EventReader`] has read it, holding at most [`EventConfig::capacity`] events.
+    pub(crate) fn new_bounded(config : EventConfig) -> Self { Self {
+        ring   : RwLock::new(EventRing::new(VecDeque::with_capacity(config.capacity))),
+        config : Some(config),
+        ..Self::new()
+    } }
+
+    /// Creates an [`EventQueue`] which keeps each event for some number of world cycles.
+    pub(crate) fn new_buffered(cycles : u64) -> Self { Self {
+        cycles : Some(cycles),
+        ..Self::new()
+    } }
+
+    /// Returns another handle to the same queue.
+    pub(super) fn share(&self) -> Self { Self {
+        ring     : RwLock::arc_clone(&self.ring),
+        cycles   : self.cycles,
+        config   : self.config,
+        dropped  : Arc::clone(&self.dropped),
+        readable : Arc::clone(&self.readable),
+        writable : Arc::clone(&self.writable)
+    } }
+
+    /// Returns a [`DroppedEvents`] resource which counts the events dropped by this queue.
+    pub(crate) fn dropped_events(&self) -> DroppedEvents<E> { DroppedEvents {
+        dropped : Arc::clone(&self.dropped),
+        marker  : PhantomData
+    } }
+
+    /// Waits until no other handle is using the ring buffer.
+    pub(super) async fn wait_unlocked(&self) {
+        drop(self.ring.write().await);
+    }
+
+    /// Sends an event to every reader, tagged with world cycle `cycle`.
+    pub(super) async fn send(&self, cycle : u64, event : E) {
+        let mut event = Some(event);
+        poll_fn(|ctx| {
+            match (self.try_send(cycle, &mut event)) {
+                Poll::Ready(true) => Poll::Ready(()),
+                // The ring buffer is only locked briefly, so try again straight away.
+                Poll::Pending => { ctx.waker().wake_by_ref(); Poll::Pending },
+                // Readers wake this once they have read an event, so the ring buffer may have room.
+                Poll::Ready(false) => {
+                    self.writable.register(ctx.waker());
+                    match (self.try_send(cycle, &mut event)) {
+                        Poll::Ready(true)  => Poll::Ready(()),
+                        Poll::Ready(false) => Poll::Pending,
+                        Poll::Pending      => { ctx.waker().wake_by_ref(); Poll::Pending }
+                    }
+                }
+            }
+        }).await
+    }
+
+    /// Sends several events to every reader, tagged with world cycle `cycle`.
+    pub(super) async fn send_batch<I : IntoIterator<Item = E> + Clone>(&self, cycle : u64, events : I) {
+        for event in events {
+            self.send(cycle, event).await;
+        }
+    }
+
+    /// Attempts to add an event to the ring buffer, following this queue's [`EventPolicy`] if it is full.
+    ///
+    /// `event` is taken once it has been sent or dropped, and `true` is returned.
+    /// If the ring buffer is full, `false` is returned. If it is locked, [`Poll::Pending`] is returned.
+    fn try_send(&self, cycle : u64, event : &mut Option<E>) -> Poll<bool> {
+        let Poll::Ready(mut ring) = self.ring.try_write() else { return Poll::Pending; };
+        ring.prune(self, cycle);
+        if (ring.readers.is_empty() && self.cycles.is_none()) {
+            // Nothing would ever read the event.
+            event.take();
+            return Poll::Ready(true);
+        }
+        if let Some(config) = self.config && (ring.events.len() >= config.capacity) {
+            match (config.policy) {
+                EventPolicy::DropOldest => {
+                    let oldest = ring.first_id;
+                    let unread = ring.readers.iter().filter(|cursor| cursor.load(Ordering::Relaxed) <= oldest).count();
+                    self.dropped.fetch_add(unread as u64, Ordering::Relaxed);
+                    ring.pop_front();
+                },
+                EventPolicy::DropNewest => {
+                    self.dropped.fetch_add(ring.readers.len() as u64, Ordering::Relaxed);
+                    event.take();
+                    return Poll::Ready(true);
+                },
+                EventPolicy::Block => { return Poll::Ready(false); }
+            }
+        }
+        if let Some(event) = event.take() {
+            ring.events.push_back((cycle, event));
+        }
+        drop(ring);
+        self.readable.wake_all();
+        Poll::Ready(true)
+    }
+
+    /// Returns `true` if an event sent in world cycle `sent` has expired as of world cycle `cycle`.
+    fn is_expired(&self, sent : u64, cycle : u64) -> bool {
+        self.cycles.is_some_and(|cycles| sent + cycles <= cycle)
+    }
+
+}
+
+
+/// The contents of an [`EventQueue`].
+struct EventRing<E : Event> {
+
+    /// The events, and the world cycles that they were sent in, from oldest to newest.
+    events   : VecDeque<(u64, E)>,
+
+    /// The ID of the oldest event. IDs increase by one with each event sent.
+    first_id : u64,
+
+    /// The ID of the next event that each [`EventReader`] will read, or [`CLOSED`] if the reader no longer exists.
+    readers  : Vec<Arc<AtomicU64>>
+
+}
+
+impl<E : Event> EventRing<E> {
+
+    /// Creates an empty [`EventRing`] using the given buffer.
+    fn new(events : VecDeque<(u64, E)>) -> Self { Self {
+        events,
+        first_id : 0,
+        readers  : Vec::new()
+    } }
+
+    /// Returns the ID that the next event sent will have.
+    fn next_id(&self) -> u64 {
+        self.first_id + (self.events.len() as u64)
+    }
+
+    /// Removes the oldest event.
+    fn pop_front(&mut self) {
+        self.events.pop_front();
+        self.first_id += 1;
+    }
+
+    /// Forgets any readers which no longer exist, and drops any events which are no longer needed as of world cycle `cycle`.
+    ///
+    /// Events are kept until they expire if the event type is buffered, or until every reader has read them otherwise.
+    fn prune(&mut self, queue : &EventQueue<E>, cycle : u64) {
+        self.readers.retain(|cursor| cursor.load(Ordering::Relaxed) != CLOSED);
+        if (queue.cycles.is_some()) {
+            while let Some((sent, _)) = self.events.front() && queue.is_expired(*sent, cycle) {
+                self.pop_front();
+            }
+        } else {
+            let read = self.readers.iter().map(|cursor| cursor.load(Ordering::Relaxed)).min().unwrap_or(u64::MAX);
+            while (self.first_id < read && ! self.events.is_empty()) {
+                self.pop_front();
+            }
+        }
+    }
+
+}
+
+
+/// Sends events of type `E` to every [`EventReader`].
+///
+/// Under the `no_std` feature, observers are not available, so unlike with `std` there is no `EventWriter::trigger`
+///  for triggering events on entities.
+pub struct EventWriter<E : Event> {
+
+    /// The queue to send events to.
+    queue : EventQueue<E>,
+
+    /// The world cycle that this writer was acquired in.
+    cycle : u64
+
+}
+
+impl<E : Event> Clone for EventWriter<E> {
+    fn clone(&self) -> Self { Self {
+        queue : self.queue.share(),
+        cycle : self.cycle
+    } }
+}
+
+unsafe impl<E : Event> Sync for EventWriter<E> { }
+unsafe impl<E : Event> Send for EventWriter<E> { }
+
+unsafe impl<E : Event + 'static> Query for EventWriter<E> {
+    type Item  = EventWriter<E>;
+    /// The [`EventQueue`], if it has been found yet.
+    type State = Option<EventQueue<E>>;
+
+    fn init_state(world : Arc<World>, _system_id : Option<SystemId>) -> Self::State {
+        match (try_event_queue::<E>(&world)) {
+            Poll::Ready(queue) => Some(queue),
+            Poll::Pending      => None
+        }
+    }
+
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        if (state.is_none()) {
+            let Poll::Ready(queue) = try_event_queue::<E>(&world) else { return Poll::Pending; };
+            *state = Some(queue);
+        }
+        // SAFETY: `state` was set above.
+        let queue = unsafe{ state.as_ref().unwrap_unchecked() };
+        Poll::Ready(QueryAcquireResult::Ready(EventWriter { queue : queue.share(), cycle : world.cycle() }))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::empty()
+    }
+}
+
+impl<E : Event> EventWriter<E> {
+
+    /// TODO: Doc comment
+    ///
+    /// If events of type `E` were added with [`EventPolicy::Block`], this waits until every reader has room for the event.
+    pub async fn send(&self, event : E) {
+        self.queue.send(self.cycle, event).await
+    }
+
+    /// TODO: Doc comment
+    pub async fn send_batch<I : IntoIterator<Item = E> + Clone>(&self, events : I) {
+        self.queue.send_batch(self.cycle, events).await
+    }
+
+    /// Returns the world cycle that this writer was acquired in, which events sent by it are tagged with.
+    ///
+    /// See [`World::cycle`] and [`EventReader::try_read_with_cycle`].
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+}
+
+
+/// TODO: Doc comment
+pub struct EventReader<E : Event> {
+
+    /// The queue to read events from.
+    queue  : EventQueue<E>,
+
+    /// The ID of the next event to read.
+    cursor : Arc<AtomicU64>,
+
+    /// The world cycle that this reader was acquired in.
+    cycle  : u64
+
+}
+
+unsafe impl<E : Event> Sync for EventReader<E> { }
+unsafe impl<E : Event> Send for EventReader<E> { }
+
+/// The state of an [`EventReader`] query.
+pub struct EventReaderState<E : Event> {
+
+    /// The [`EventQueue`] that this reader's cursor was registered with, if it has been.
+    queue  : Option<EventQueue<E>>,
+
+    /// The ID of the next event to read.
+    cursor : Arc<AtomicU64>
+
+}
+
+unsafe impl<E : Event + 'static> Query for EventReader<E> {
+    type Item  = EventReader<E>;
+    type State = EventReaderState<E>;
+
+    fn init_state(world : Arc<World>, _system_id : Option<SystemId>) -> Self::State {
+        let mut state = EventReaderState::new();
+        let _ = Self::try_register(&world, &mut state);
+        state
+    }
+
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        if (Self::try_register(&world, state).is_pending()) {
+            return Poll::Pending;
+        }
+        Poll::Ready(QueryAcquireResult::Ready(EventReader::from_state(state, world.cycle())))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::empty()
+    }
+}
+
+impl<E : Event> EventReaderState<E> {
+
+    /// Creates the state of a reader which has not been registered with the [`EventQueue`] yet.
+    pub(super) fn new() -> Self { Self {
+        queue  : None,
+        cursor : Arc::new(AtomicU64::new(0))
+    } }
+
+}
+
+impl<E : Event> Drop for EventReaderState<E> {
+    fn drop(&mut self) {
+        // The cursor is forgotten by the queue the next time it is pruned, which may make room for blocked writers.
+        self.cursor.store(CLOSED, Ordering::Relaxed);
+        if let Some(queue) = &self.queue {
+            queue.writable.wake_all();
+        }
+    }
+}
+
+impl<E : Event + 'static> EventReader<E> {
+
+    /// Attempts to register a reader's cursor with the [`EventQueue`], if it hasn't been already.
+    ///
+    /// The reader starts after the newest event, or at the oldest event still in the ring buffer if the event type is buffered.
+    fn try_register(world : &World, state : &mut EventReaderState<E>) -> Poll<()> {
+        if (state.queue.is_some()) { return Poll::Ready(()); }
+        let Poll::Ready(queue) = try_event_queue::<E>(world) else { return Poll::Pending; };
+        Self::try_register_with(queue, state)
+    }
+
+    /// Attempts to register a reader's cursor with `queue`, if it hasn't been already.
+    ///
+    /// See [`EventReader::try_register`].
+    pub(super) fn try_register_with(queue : EventQueue<E>, state : &mut EventReaderState<E>) -> Poll<()> {
+        if (state.queue.is_some()) { return Poll::Ready(()); }
+        let Poll::Ready(mut ring) = queue.ring.try_write() else { return Poll::Pending; };
+        let start = if (queue.cycles.is_some()) { ring.first_id } else { ring.next_id() };
+        state.cursor.store(start, Ordering::Relaxed);
+        ring.readers.push(Arc::clone(&state.cursor));
+        drop(ring);
+        state.queue = Some(queue);
+        Poll::Ready(())
+    }
+
+    /// Creates a reader from the state of a registered reader, acquired in world cycle `cycle`.
+    ///
+    /// ### Panics
+    /// Panics if the reader has not been registered.
+    pub(super) fn from_state(state : &EventReaderState<E>, cycle : u64) -> Self {
+        let queue = state.queue.as_ref().expect("Event reader has not been registered");
+        Self {
+            queue  : queue.share(),
+            cursor : Arc::clone(&state.cursor),
+            cycle
+        }
+    }
+
+}
+
+impl<E : Event> EventReader<E> {
+    /// Sets the world cycle that this reader considers to be the current one.
+    pub(super) fn set_cycle(&mut self, cycle : u64) {
+        self.cycle = cycle;
+    }
+
+
+    /// Waits for the next event, without blocking the thread.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Event, Clone)]
+    /// struct Damage(u32);
+    ///
+    /// // Waits for the next three hits, so this system finishes once they have arrived.
+    /// async fn log_next_hits(
+    ///     damage : EventReader<Damage>
+    /// ) {
+    ///     for _ in 0..3 {
+    ///         let Ok(Damage(amount)) = damage.recv().await else { break; };
+    ///         println!("Took {} damage", amount);
+    ///     }
+    /// }
+    /// ```
+    pub async fn recv(&self) -> Result<E, RecvError> {
+        Ok(poll_fn(|ctx| match (self.poll_read()) {
+            Poll::Ready(Some((_, event))) => Poll::Ready(event),
+            // The ring buffer is only locked briefly, so try again straight away.
+            Poll::Pending => { ctx.waker().wake_by_ref(); Poll::Pending },
+            // Writers wake this once they have sent an event. Check again in case one was sent before the waker was registered.
+            Poll::Ready(None) => {
+                self.queue.readable.register(ctx.waker());
+                match (self.poll_read()) {
+                    Poll::Ready(Some((_, event))) => Poll::Ready(event),
+                    Poll::Ready(None)             => Poll::Pending,
+                    Poll::Pending                 => { ctx.waker().wake_by_ref(); Poll::Pending }
+                }
+            }
+        }).await)
+    }
+
+    /// TODO: Doc comment
+    pub fn try_read(&self) -> Result<E, TryRecvError> {
+        self.try_read_with_cycle().map(|(_, event)| event)
+    }
+
+    /// Returns the next event if there is one, along with the world cycle that it was sent in. See [`World::cycle`].
+    ///
+    /// If the ring buffer is being written to, this returns [`TryRecvError`] even if there are events waiting.
+    pub fn try_read_with_cycle(&self) -> Result<(u64, E), TryRecvError> {
+        match (self.poll_read()) {
+            Poll::Ready(Some(event)) => Ok(event),
+            Poll::Ready(None) | Poll::Pending => Err(TryRecvError)
+        }
+    }
+
+    /// Returns the next event if there is one, along with the world cycle that it was sent in.
+    ///
+    /// If the ring buffer is being written to, [`Poll::Pending`] is returned. Writers waiting for room are woken once an event is read.
+    fn poll_read(&self) -> Poll<Option<(u64, E)>> {
+        let Poll::Ready(ring) = self.queue.ring.try_read() else { return Poll::Pending; };
+        let mut id = self.cursor.load(Ordering::Relaxed).max(ring.first_id);
+        while let Some((sent, event)) = ring.events.get((id - ring.first_id) as usize) {
+            id += 1;
+            if (! self.queue.is_expired(*sent, self.cycle)) {
+                self.cursor.store(id, Ordering::Relaxed);
+                let event = (*sent, event.clone());
+                drop(ring);
+                self.queue.writable.wake_all();
+                return Poll::Ready(Some(event));
+            }
+        }
+        Poll::Ready(None)
+    }
+
+    /// Returns the number of events waiting to be read by this reader.
+    pub fn len(&self) -> usize {
+        let Poll::Ready(ring) = self.queue.ring.try_read() else { return 0; };
+        let id = self.cursor.load(Ordering::Relaxed).max(ring.first_id);
+        ring.events.range(((id - ring.first_id) as usize)..).filter(|(sent, _)| ! self.queue.is_expired(*sent, self.cycle)).count()
+    }
+
+    /// Returns `true` if there are no events waiting to be read by this reader.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Marks every event waiting to be read by this reader as read.
+    pub fn clear(&self) {
+        while (self.try_read_with_cycle().is_ok()) { }
+    }
+
+}
+
+impl<E : Event> Iterator for EventReader<E> {
+    type Item = E;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_read().ok()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::task::{ Context, Waker };
+    use alloc::boxed::Box;
+    use alloc::task::Wake;
+    use async_std::task::block_on;
+
+    impl Event for usize { }
+
+    #[test]
+    fn readers_share_ring() { block_on(async {
+        let world = Arc::new(World::new());
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut first  = world.query_mut::<EventReader<usize>>();
+        let mut second = world.query_mut::<EventReader<usize>>();
+        let writer = writer.acquire().await;
+        let first  = first.acquire().await;
+        writer.send_batch(0..3).await;
+        assert_eq!(first.collect::<Vec<_>>(), [0, 1, 2]);
+        writer.send(3).await;
+        let second = second.acquire().await;
+        assert_eq!(second.len(), 4);
+        assert_eq!(second.collect::<Vec<_>>(), [0, 1, 2, 3]);
+        // Events are dropped once every reader has read them.
+        writer.send(4).await;
+        let Poll::Ready(ring) = writer.queue.ring.try_read() else { panic!("Event queue is locked"); };
+        assert_eq!(ring.events.len(), 2);
+    }); }
+
+    #[test]
+    fn full_ring_follows_policy() { block_on(async {
+        for (policy, first) in [(EventPolicy::DropOldest, 2), (EventPolicy::DropNewest, 0)] {
+            let world = Arc::new(World::new());
+            let queue = EventQueue::<usize>::new_bounded(EventConfig { capacity : 3, policy });
+            let dropped = queue.dropped_events();
+            world.insert_resource(queue).await;
+            let mut writer = world.query_mut::<EventWriter<usize>>();
+            let mut reader = world.query_mut::<EventReader<usize>>();
+            let reader = reader.acquire().await;
+            writer.acquire().await.send_batch(0..5).await;
+            assert_eq!(dropped.count(), 2);
+            assert_eq!(reader.collect::<Vec<_>>(), (first..(first + 3)).collect::<Vec<_>>());
+        }
+    }); }
+
+    #[test]
+    fn buffered_events_expire_after_cycles() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(EventQueue::<usize>::new_buffered(2)).await;
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut early  = world.query_mut::<EventReader<usize>>();
+        writer.acquire().await.send(0).await;
+        world.advance_cycle();
+        writer.acquire().await.send(1).await;
+
+        // Readers see events sent before they were created, if they are still buffered.
+        let mut late = world.query_mut::<EventReader<usize>>();
+        assert_eq!(late.acquire().await.collect::<Vec<_>>(), [0, 1]);
+        let reader = early.acquire().await;
+        assert_eq!(reader.try_read_with_cycle(), Ok((0, 0)));
+        assert_eq!(reader.try_read_with_cycle(), Ok((1, 1)));
+        drop(reader);
+
+        // Events are dropped once they have been kept for 2 cycles after the one they were sent in.
+        writer.acquire().await.send(2).await;
+        world.advance_cycle();
+        assert_eq!(world.query_mut::<EventReader<usize>>().acquire().await.collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(early.acquire().await.collect::<Vec<_>>(), [2]);
+        world.advance_cycle();
+        world.advance_cycle();
+        assert!(world.query_mut::<EventReader<usize>>().acquire().await.is_empty());
+
+        // Waiting readers are woken once an event is sent.
+        let reader   = late.acquire().await;
+        let mut ctx  = Context::from_waker(Waker::noop());
+        let mut recv = Box::pin(reader.recv());
+        assert!(recv.as_mut().poll(&mut ctx).is_pending());
+        writer.acquire().await.send(3).await;
+        assert_eq!(recv.as_mut().poll(&mut ctx), Poll::Ready(Ok(3)));
+    }); }
+
+    /// A waker which counts the number of times it has been woken.
+    struct CountingWaker(AtomicU64);
+    impl Wake for CountingWaker {
+        fn wake(self : Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn waiting_is_woken_without_polling() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(EventQueue::<usize>::new_bounded(EventConfig { capacity : 1, policy : EventPolicy::Block })).await;
+        let mut writer = world.query_mut::<EventWriter<usize>>();
+        let mut reader = world.query_mut::<EventReader<usize>>();
+        let writer = writer.acquire().await;
+        let reader = reader.acquire().await;
+        let counter = Arc::new(CountingWaker(AtomicU64::new(0)));
+        let waker   = Waker::from(Arc::clone(&counter));
+        let mut ctx = Context::from_waker(&waker);
+
+        // Readers are woken once an event is sent.
+        let mut recv = Box::pin(reader.recv());
+        assert!(recv.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+        writer.send(0).await;
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert_eq!(recv.as_mut().poll(&mut ctx), Poll::Ready(Ok(0)));
+
+        // Blocked writers are woken once a reader makes room.
+        writer.send(1).await;
+        let mut send = Box::pin(writer.send(2));
+        assert!(send.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert_eq!(reader.try_read(), Ok(1));
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+        assert!(send.as_mut().poll(&mut ctx).is_ready());
+        assert_eq!(reader.try_read(), Ok(2));
+    }); }
+
+}
@@ -0,0 +1,112 @@
+//! Per-system state.
+
+
+use crate::world::World;
+use crate::system::SystemId;
+use crate::query::{ Query, QueryAcquireResult, QueryValidator };
+use crate::util::rwlock::{ RwLock, RwLockWriteGuard };
+use core::ops::{ Deref, DerefMut };
+use core::task::Poll;
+use core::fmt;
+use alloc::sync::Arc;
+
+
+/// A value which is private to a single system, and persists between runs of it.
+///
+/// The value is created with [`Default`] when the system is created, and is stored alongside the system's other query state.
+/// No [`World`] locks are needed to access it. Each [`Local`] holds a lock on the value, so it can not outlive the state it came from.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+///
+/// async fn count_runs(
+///     mut runs : Local<usize>
+/// ) {
+///     *runs += 1;
+///     println!("This system has run {} times.", *runs);
+/// }
+/// ```
+pub struct Local<T : Default> {
+
+    /// The guard keeping the value in the system's query state locked.
+    guard : RwLockWriteGuard<T>
+
+}
+
+unsafe impl<T : Default + Send> Send for Local<T> { }
+unsafe impl<T : Default + Sync> Sync for Local<T> { }
+
+
+unsafe impl<T : Default> Query for Local<T> {
+    type Item = Local<T>;
+    type State = RwLock<T>;
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State {
+        RwLock::new(T::default())
+    }
+
+    unsafe fn acquire(_world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        match (state.try_write()) {
+            Poll::Ready(guard) => Poll::Ready(QueryAcquireResult::Ready(Local { guard })),
+            Poll::Pending      => Poll::Pending
+        }
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::empty()
+    }
+
+}
+
+
+impl<T : Default> Deref for Local<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T : Default> DerefMut for Local<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T : Default + fmt::Debug> fmt::Debug for Local<T> {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Local").field(&**self).finish()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+
+    async fn count_runs(mut runs : Local<usize>, mut doubled : Local<usize>) -> (usize, usize) {
+        *runs    += 1;
+        *doubled += 2;
+        (*runs, *doubled)
+    }
+
+    #[test]
+    fn locals_persist_per_system() { block_on(async {
+        let world     = Arc::new(World::new());
+        let mut first = world.system_mut(count_runs);
+        assert_eq!(first.run().await, (1, 2));
+        assert_eq!(first.run().await, (2, 4));
+        let mut second = world.system_mut(count_runs);
+        assert_eq!(second.run().await, (1, 2));
+        assert_eq!(first.run().await, (3, 6));
+    }); }
+
+    #[test]
+    fn locals_request_nothing_from_the_world() {
+        assert!(<Local<usize> as Query>::validate().is_empty());
+    }
+
+}
@@ -4,9 +4,10 @@
 mod scoped;
 pub use scoped::*;
 
-#[cfg(not(feature = "no_std"))]
+mod local;
+pub use local::*;
+
 mod event;
-#[cfg(not(feature = "no_std"))]
 pub use event::*;
 
 
@@ -48,6 +49,7 @@ unsafe impl<Q : Query> Query for Option<Q> {
         match (unsafe{ <Q as Query>::acquire(world, state) }) {
             Poll::Ready(QueryAcquireResult::Ready(out))          => Poll::Ready(QueryAcquireResult::Ready(Some(out))),
             Poll::Ready(QueryAcquireResult::DoesNotExist { .. }) => Poll::Ready(QueryAcquireResult::Ready(None)),
+            Poll::Ready(QueryAcquireResult::NotUnique { .. })    => Poll::Ready(QueryAcquireResult::Ready(None)),
             Poll::Pending                                        => Poll::Pending
         }
     }
@@ -84,6 +86,10 @@ macro impl_query_for_tuple( $( #[$meta:meta] )* $( $generic:ident ),* $(,)? ) {
                     Poll::Ready(QueryAcquireResult::DoesNotExist { name }) => { return Poll::Ready(QueryAcquireResult::DoesNotExist { name }); },
                     #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
                     Poll::Ready(QueryAcquireResult::DoesNotExist { })      => { return Poll::Ready(QueryAcquireResult::DoesNotExist {  }); },
+                    #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                    Poll::Ready(QueryAcquireResult::NotUnique { name, count }) => { return Poll::Ready(QueryAcquireResult::NotUnique { name, count }); },
+                    #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+                    Poll::Ready(QueryAcquireResult::NotUnique { count })       => { return Poll::Ready(QueryAcquireResult::NotUnique { count }); },
                     Poll::Pending                                          => { return Poll::Pending; }
                 };
             )*
@@ -102,3 +108,60 @@ macro impl_query_for_tuple( $( #[$meta:meta] )* $( $generic:ident ),* $(,)? ) {
     unsafe impl< $( $generic : ReadOnlyQuery ),* > ReadOnlyQuery for ( $( $generic , )* ) { }
 
 }
+
+
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::prelude::*;
+    use crate::query::{ Query, ReadOnlyQuery };
+    use alloc::sync::Arc;
+    use async_std::task::block_on;
+
+    #[derive(Resource)]
+    struct Value<const N : usize>(usize);
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[derive(Query)]
+    struct Named<'l> {
+        cmds     : Commands,
+        value    : Res<&'l Value<0>>,
+        entities : Entities<(&'static Marker,)>
+    }
+
+    #[derive(Query)]
+    struct Unnamed<'l>(Res<&'l Value<0>>, Option<Res<&'l mut Value<1>>>);
+
+    #[derive(Query)]
+    struct Keyed<'l, const N : usize> {
+        value : Res<&'l Value<N>>
+    }
+
+    #[derive(Query)]
+    struct Empty;
+
+    fn is_read_only<Q : ReadOnlyQuery>() { }
+
+    #[test]
+    fn derived_queries() { block_on(async {
+        is_read_only::<Keyed<'static, 0>>();
+        is_read_only::<Empty>();
+        assert!(! <Named<'static> as Query>::validate().is_empty());
+        let world = Arc::new(World::new());
+        world.insert_resource(Value::<0>(5)).await;
+        world.insert_resource(Value::<2>(7)).await;
+        world.spawn_batch((0..3).map(|_| (Marker,))).await.for_each(drop);
+
+        assert_eq!(world.system_mut(async |params : Named<'_>, _empty : Empty| {
+            assert!(! params.cmds.is_exiting());
+            params.value.0 + params.entities.iter().count()
+        }).run().await, 8);
+        assert_eq!(world.system_mut(async |Unnamed(value, missing) : Unnamed<'_>| {
+            (value.0, missing.is_none())
+        }).run().await, (5, true));
+        assert_eq!(world.system(async |params : Keyed<'_, 2>| params.value.0).run().await, 7);
+    }) }
+
+}
@@ -44,6 +44,11 @@ impl<Q : Query> Scoped<Q> {
             #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
             QueryAcquireResult::DoesNotExist {      } => QueryAcquireResult::DoesNotExist {      },
 
+            #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+            QueryAcquireResult::NotUnique { name, count } => QueryAcquireResult::NotUnique { name, count },
+            #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+            QueryAcquireResult::NotUnique {       count } => QueryAcquireResult::NotUnique {       count },
+
         }
     }
 
@@ -2,7 +2,10 @@
 
 
 mod impls;
-pub use impls::{ Scoped, Event, EventReader, EventWriter };
+pub use impls::{ Scoped, Local, Event, EventReader, EventWriter, EventSender, EventReceiver, EventConfig, EventPolicy, DroppedEvents };
+#[cfg(feature = "no_std")]
+pub use impls::{ TryRecvError, RecvError };
+pub(crate) use impls::EventQueue;
 
 mod validate;
 pub use validate::*;
@@ -20,6 +23,11 @@ use core::hint::unreachable_unchecked;
 use alloc::sync::Arc;
 
 
+/// Re-exported for the [`Query`] derive macro, which can not assume that `std` is available.
+#[doc(hidden)]
+pub use alloc::sync::Arc as __Arc;
+
+
 /// TODO: Doc comments
 #[diagnostic::on_unimplemented(
     message = "`{Self}` is not a valid query"
@@ -75,6 +83,16 @@ pub enum QueryAcquireResult<T> {
         #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
         #[doc(cfg(feature = "keep_debug_names"))]
         name : &'static str
+    },
+
+    /// More than one value matched a [`Query`] which requires exactly one, such as [`Single`](crate::entity::Single).
+    NotUnique {
+        /// The [`type_name`](::core::any::type_name) of the [`Query`].
+        #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+        #[doc(cfg(feature = "keep_debug_names"))]
+        name  : &'static str,
+        /// The number of values which matched.
+        count : usize
     }
 
 }
@@ -93,6 +111,30 @@ impl<T> QueryAcquireResult<T> {
             #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
             QueryAcquireResult::DoesNotExist { } => { panic!("{} requested non-existent item", source) }
 
+            #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+            QueryAcquireResult::NotUnique { name, count } => { panic!("{} requested {}, but {} entities matched instead of exactly one", source, unsafe{ UnqualifiedTypeName::from_unchecked(name) }, count) }
+            #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+            QueryAcquireResult::NotUnique { count } => { panic!("{} requested a single entity, but {} entities matched instead of exactly one", source, count) }
+
+        }
+    }
+
+    /// Maps the value in [`QueryAcquireResult::Ready`] using the given function, leaving [`QueryAcquireResult::DoesNotExist`] untouched.
+    pub fn map<U, F : FnOnce(T) -> U>(self, f : F) -> QueryAcquireResult<U> {
+        match (self) {
+
+            QueryAcquireResult::Ready(out) => QueryAcquireResult::Ready(f(out)),
+
+            #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+            QueryAcquireResult::DoesNotExist { name } => QueryAcquireResult::DoesNotExist { name },
+            #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+            QueryAcquireResult::DoesNotExist { } => QueryAcquireResult::DoesNotExist { },
+
+            #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+            QueryAcquireResult::NotUnique { name, count } => QueryAcquireResult::NotUnique { name, count },
+            #[cfg(not(any(debug_assertions, feature = "keep_debug_names")))]
+            QueryAcquireResult::NotUnique { count } => QueryAcquireResult::NotUnique { count }
+
         }
     }
 
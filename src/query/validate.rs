@@ -12,7 +12,10 @@ use alloc::collections::BTreeSet;
 pub struct QueryValidator {
 
     /// The value types that the [`Query`](crate::query::Query) accesses, how they are accessed, and whether they conflict.
-    entries : BTreeSet<QueryValidatorEntry>
+    entries   : BTreeSet<QueryValidatorEntry>,
+
+    /// Whether the [`Query`](crate::query::Query) requests exclusive access to the entire [`World`](crate::world::World).
+    exclusive : bool
 
 }
 
@@ -75,7 +78,8 @@ impl QueryValidator {
     ///
     /// No values are requested by the [`Query`](crate::query::Query).
     pub fn empty() -> Self { Self {
-        entries : BTreeSet::new()
+        entries   : BTreeSet::new(),
+        exclusive : false
     } }
 
     /// Creates a new [`QueryValidator`] from a type `T` and [`QueryValidatorEntryState`].
@@ -84,7 +88,7 @@ impl QueryValidator {
     ) -> Self {
         let mut entries = BTreeSet::new();
         entries.insert(QueryValidatorEntry::of::<T>(state));
-        Self { entries }
+        Self { entries, exclusive : false }
     }
 
     /// Creates a [`QueryValidator`] with a single entry.
@@ -114,6 +118,31 @@ impl QueryValidator {
         QueryValidatorEntryState::Owned
     ) }
 
+    /// Creates a [`QueryValidator`] with no entries.
+    ///
+    /// The [`Query`](crate::query::Query) requests exclusive access to the entire [`World`](crate::world::World).
+    ///
+    /// Systems with exclusive access are run by the scheduler while no other systems hold any world locks.
+    /// No other access of any type is allowed in the same system, as those values could not be accessed through the world while they are held.
+    pub fn of_exclusive() -> Self { Self {
+        entries   : BTreeSet::new(),
+        exclusive : true
+    } }
+
+    /// Returns `true` if the [`Query`](crate::query::Query) requests exclusive access to the entire [`World`](crate::world::World).
+    ///
+    /// See [`QueryValidator::of_exclusive`].
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Returns `true` if the [`Query`](crate::query::Query) requests no values, and not exclusive access to the entire [`World`](crate::world::World).
+    ///
+    /// Systems whose queries request nothing hold no locks on the world while they run.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && (! self.exclusive)
+    }
+
     /// Joins two [`QueryValidator`]s together.
     ///
     /// If any entries conflict, an error is stored and will be included in the panic message from [`QueryValidator::panic_on_violation`].
@@ -129,6 +158,7 @@ impl QueryValidator {
                 a.entries.insert(b_entry);
             }
         }
+        a.exclusive |= b.exclusive;
         a
     }
 
@@ -138,16 +168,26 @@ impl QueryValidator {
     /// Panics if any requested values conflict with each other.
     #[track_caller]
     pub fn panic_on_violation(&self) {
-        if (self.entries.iter().any(|entry| entry.state.is_error())) {
+        if (self.entries.iter().any(|entry| entry.state.is_error()) || self.is_exclusive_error()) {
             panic!("{}", self);
         }
     }
 
+    /// Returns `true` if exclusive access to the entire [`World`](crate::world::World) was requested alongside other values.
+    fn is_exclusive_error(&self) -> bool {
+        self.exclusive && (! self.entries.is_empty())
+    }
+
 }
 
 impl fmt::Display for QueryValidator {
     fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut has_errors = false;
+        if (self.is_exclusive_error()) {
+            write!(f, "Query would violate the borrow checker rules:")?;
+            write!(f, "\n  Already took exclusive access to the world")?;
+            has_errors = true;
+        }
         for entry in &self.entries {
             if (entry.state.is_error()) {
                 if (! has_errors) {
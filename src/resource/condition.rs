@@ -0,0 +1,103 @@
+//! Run conditions for [`Resource`]s.
+//!
+//! See [`IntoScheduledSystemConfig::run_if`](crate::schedule::system::IntoScheduledSystemConfig::run_if).
+
+
+use crate::resource::{ Resource, Res };
+
+
+/// Returns a run condition which passes if a [`Resource`] of type `R` exists.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use axecs::resource::resource_exists;
+///
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// let mut app = App::new();
+/// app.add_systems(Cycle, print_score.run_if(resource_exists::<Score>()));
+///
+/// async fn print_score(
+///     score : Res<&Score>
+/// ) {
+///     println!("{}", score.0);
+/// }
+/// ```
+pub fn resource_exists<R : Resource + 'static>() -> impl AsyncFnMut(Option<Res<&'static R>>) -> bool + Send + Sync + 'static {
+    async |resource : Option<Res<&'static R>>| resource.is_some()
+}
+
+/// Returns a run condition which passes if a [`Resource`] of type `R` was inserted since the condition last ran.
+///
+/// The first time the condition runs, every existing [`Resource`] is considered added. See [`Res::is_added`].
+pub fn resource_added<R : Resource + 'static>() -> impl AsyncFnMut(Option<Res<&'static R>>) -> bool + Send + Sync + 'static {
+    async |resource : Option<Res<&'static R>>| resource.is_some_and(|resource| resource.is_added())
+}
+
+/// Returns a run condition which passes if a [`Resource`] of type `R` was inserted or accessed mutably since the condition last ran.
+///
+/// The first time the condition runs, every existing [`Resource`] is considered changed. See [`Res::is_changed`].
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use axecs::resource::resource_changed;
+///
+/// #[derive(Resource)]
+/// struct Config {
+///     scale : f32
+/// }
+///
+/// let mut app = App::new();
+/// app.add_systems(Cycle, rebuild_cache.run_if(resource_changed::<Config>()));
+///
+/// async fn rebuild_cache(
+///     config : Res<&Config>
+/// ) {
+///     println!("Rebuilding with scale {}", config.scale);
+/// }
+/// ```
+pub fn resource_changed<R : Resource + 'static>() -> impl AsyncFnMut(Option<Res<&'static R>>) -> bool + Send + Sync + 'static {
+    async |resource : Option<Res<&'static R>>| resource.is_some_and(|resource| resource.is_changed())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::World;
+    use alloc::sync::Arc;
+    use async_std::task::block_on;
+
+    struct Score(u32);
+    impl Resource for Score { }
+
+    #[test]
+    fn conditions_follow_the_resource() { block_on(async {
+        let world       = Arc::new(World::new());
+        let mut exists  = world.system(resource_exists::<Score>());
+        let mut added   = world.system(resource_added::<Score>());
+        let mut changed = world.system(resource_changed::<Score>());
+        assert!(! exists.run().await && ! added.run().await && ! changed.run().await);
+
+        world.insert_resource(Score(0)).await;
+        assert!(exists.run().await && added.run().await && changed.run().await);
+        assert!(exists.run().await && ! added.run().await && ! changed.run().await);
+
+        world.query_mut::<Res<&mut Score>>().acquire().await.0 += 1;
+        assert!(! added.run().await && changed.run().await);
+        assert!(! added.run().await && ! changed.run().await);
+    }); }
+
+    #[test]
+    fn conditions_see_existing_resources_as_added() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Score(0)).await;
+        assert!(world.system(resource_added::<Score>()).run().await);
+        assert!(world.system(resource_changed::<Score>()).run().await);
+    }); }
+
+}
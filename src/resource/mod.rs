@@ -7,6 +7,12 @@ pub use storage::*;
 mod query;
 pub use query::*;
 
+mod non_send;
+pub use non_send::*;
+
+mod condition;
+pub use condition::*;
+
 
 /// TODO: Doc comments
 pub trait Resource : Sized + Send + Sync { }
@@ -16,8 +22,13 @@ pub trait Resource : Sized + Send + Sync { }
 pub(crate) mod marker {
     use core::marker::PhantomData;
     /// Used in error messages and [`TypeId`](::core::any::TypeId) comparisons to indicate that a type is a [`Resource`](super::Resource).
-    pub(super) struct Resource<C : super::Resource> {
-        /// [`PhantomData`] on `C`.
-        marker : PhantomData<C>
+    pub(super) struct Resource<C : super::Resource, K : 'static = ()> {
+        /// [`PhantomData`] on `C` and its key `K`.
+        marker : PhantomData<(C, fn() -> K)>
+    }
+    /// Used in error messages and [`TypeId`](::core::any::TypeId) comparisons to indicate that a type is a non-send resource.
+    pub(super) struct NonSend<R : 'static> {
+        /// [`PhantomData`] on `R`.
+        marker : PhantomData<R>
     }
 }
@@ -0,0 +1,359 @@
+//! Resources which can not be sent between threads.
+
+
+use crate::world::World;
+use crate::resource::{ self, ResourceCell };
+use crate::system::SystemId;
+use crate::query::{ Query, ReadOnlyQuery, QueryAcquireResult, QueryValidator };
+use crate::util::rwlock::{ RwLock, RwLockReadGuard, RwLockWriteGuard };
+use core::ops::{ Deref, DerefMut };
+use core::any::TypeId;
+#[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+use core::any::type_name;
+use core::task::Poll;
+use core::marker::PhantomData;
+use core::mem;
+use alloc::sync::Arc;
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "no_std"))]
+use std::thread::{ self, ThreadId };
+
+
+/// A container for values which do not need to be [`Send`] or [`Sync`].
+///
+/// Unlike [`ResourceStorage`](crate::resource::ResourceStorage), the values in here can only be accessed from the thread which created this storage.
+/// Accessing them from any other thread will panic.
+///
+/// The [`CycleSchedulerPlugin`](crate::app::plugin::CycleSchedulerPlugin) creates this storage inside the future returned by
+///  [`App::run`](crate::app::App::run), and polls every system inline within that same future instead of spawning them.
+/// Systems requesting [`NonSend`] can therefore access their values as long as that future is driven on a single thread,
+///  such as with `block_on` or `async_std::main`. Executors which move a task between threads will cause those accesses to panic.
+pub struct NonSendStorage {
+
+    /// The thread which created this storage.
+    #[cfg(not(feature = "no_std"))]
+    owner : ThreadId,
+
+    /// The raw values.
+    raw   : RwLock<RawNonSendStorage>
+
+}
+
+/// The values in a [`NonSendStorage`], before it has been given to a [`World`].
+pub struct RawNonSendStorage {
+
+    /// The [`RwLock`] wrapped [`ResourceCell`]s, by type.
+    resources : BTreeMap<TypeId, RwLock<ResourceCell>>,
+
+    /// Prevents this storage from being sent to another thread.
+    marker    : PhantomData<*const ()>
+
+}
+
+
+impl NonSendStorage {
+
+    /// Creates an empty [`NonSendStorage`], owned by the current thread.
+    pub fn new() -> Self {
+        Self::new_with(RawNonSendStorage::new())
+    }
+
+    /// Creates a [`NonSendStorage`] with some values in it to start, owned by the current thread.
+    pub fn new_with(raw : RawNonSendStorage) -> Self { Self {
+        #[cfg(not(feature = "no_std"))]
+        owner : thread::current().id(),
+        raw   : RwLock::new(raw)
+    } }
+
+    /// Returns `true` if the values in this storage can be accessed from the current thread.
+    pub fn is_owner(&self) -> bool {
+        #[cfg(not(feature = "no_std"))]
+        { thread::current().id() == self.owner }
+        #[cfg(feature = "no_std")]
+        { true }
+    }
+
+    /// Panics if the current thread does not own this storage.
+    #[track_caller]
+    fn assert_owner(&self) {
+        if (! self.is_owner()) {
+            panic!("Non-send resources can only be accessed from the thread that owns the world");
+        }
+    }
+
+    /// Inserts a value into this storage, overwriting any previous value of the same type.
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this storage.
+    #[track_caller]
+    pub async fn insert<R : 'static>(&self, resource : R) {
+        self.assert_owner();
+        self.raw.write().await.resources.insert(TypeId::of::<R>(), RwLock::new(ResourceCell::new(resource)));
+    }
+
+    /// Removes a value from this storage.
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this storage.
+    #[track_caller]
+    pub async fn remove<R : 'static>(&self) {
+        self.assert_owner();
+        self.raw.write().await.resources.remove(&TypeId::of::<R>());
+    }
+
+    /// Removes a value from this storage, returning it if it existed.
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this storage.
+    #[track_caller]
+    pub async fn take<R : 'static>(&self) -> Option<R> {
+        self.assert_owner();
+        let lock = self.raw.write().await.resources.remove(&TypeId::of::<R>())?;
+        // SAFETY: The cell was stored under the `TypeId` of `R`.
+        Some(unsafe{ lock.into_inner().await.read() })
+    }
+
+}
+
+impl Default for NonSendStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NonSendStorage {
+    fn drop(&mut self) {
+        // An empty storage has nothing which needs to be dropped on the owning thread.
+        let is_empty = match (self.raw.try_read()) {
+            Poll::Ready(raw) => raw.resources.is_empty(),
+            Poll::Pending    => false
+        };
+        if (! (self.is_owner() || is_empty)) {
+            // The values can not be dropped on this thread, so they are leaked instead.
+            mem::forget(mem::replace(&mut self.raw, RwLock::new(RawNonSendStorage::new())));
+            #[cfg(not(feature = "no_std"))]
+            if (! thread::panicking()) {
+                panic!("Non-send resources were dropped from a thread that does not own the world");
+            }
+        }
+    }
+}
+
+impl RawNonSendStorage {
+
+    /// Creates an empty [`RawNonSendStorage`].
+    pub fn new() -> Self { Self {
+        resources : BTreeMap::new(),
+        marker    : PhantomData
+    } }
+
+    /// Inserts a value into this storage.
+    ///
+    /// Returns `false` if a value of the same type has already been inserted.
+    pub fn insert<R : 'static>(&mut self, resource : R) -> bool {
+        self.resources.try_insert(TypeId::of::<R>(), RwLock::new(ResourceCell::new(resource))).is_ok()
+    }
+
+}
+
+impl Default for RawNonSendStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// TODO: Doc comment
+mod __internal {
+
+    /// TODO: Doc comment
+    pub trait NonSendInner {
+        /// TODO: Doc comment
+        type Guard;
+    }
+
+}
+use __internal::*;
+
+
+/// A [`Query`] for a value in the [`NonSendStorage`] of a [`World`].
+///
+/// Use `NonSend<&R>` for immutable access, or `NonSend<&mut R>` for mutable access.
+/// This can not be sent to another thread, so a system requesting it will stay on the thread that owns the [`World`].
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use std::rc::Rc;
+/// # use async_std::main;
+///
+/// struct ScriptEngine {
+///     state : Rc<usize>
+/// }
+///
+/// #[main]
+/// async fn main() {
+///     let mut app = App::new();
+///     app.add_plugin(CycleSchedulerPlugin);
+///     app.insert_non_send_resource(ScriptEngine { state : Rc::new(123) });
+///     app.add_systems(Cycle, run_scripts);
+///     app.run().await;
+/// }
+///
+/// async fn run_scripts(
+///     cmds   : Commands,
+///     engine : NonSend<&ScriptEngine>
+/// ) {
+///     println!("{}", engine.state);
+///     # cmds.try_exit(AppExit::Ok);
+/// }
+/// ```
+///
+/// ### Panics
+/// Acquiring this query panics if the current thread does not own the [`World`].
+pub struct NonSend<R : NonSendInner> {
+
+    /// The guard keeping the value locked.
+    guard  : R::Guard,
+
+    /// Prevents this query from being sent to another thread.
+    marker : PhantomData<*const ()>
+
+}
+
+
+/// Attempts to lock a [`ResourceCell`] in the [`NonSendStorage`] of a [`World`].
+fn acquire_non_send<R : 'static, G>(world : &World, lock : impl FnOnce(&RwLock<ResourceCell>) -> Poll<G>) -> Poll<QueryAcquireResult<G>> {
+    let storage = world.non_send_resources();
+    storage.assert_owner();
+    match (storage.raw.try_read()) {
+        Poll::Ready(inner) => {
+            match (inner.resources.get(&TypeId::of::<R>())) {
+                Some(cell) => match (lock(cell)) {
+                    Poll::Ready(out) => Poll::Ready(QueryAcquireResult::Ready(out)),
+                    Poll::Pending    => Poll::Pending
+                },
+                None => Poll::Ready(QueryAcquireResult::DoesNotExist {
+                    #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
+                    name : type_name::<resource::marker::NonSend<R>>()
+                })
+            }
+        },
+        Poll::Pending => Poll::Pending
+    }
+}
+
+
+impl<R : 'static> NonSendInner for &R {
+    type Guard = RwLockReadGuard<ResourceCell>;
+}
+
+unsafe impl<'l, R : 'static> Query for NonSend<&'l R> {
+    type Item = NonSend<&'l R>;
+    type State = ();
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+
+    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        match (acquire_non_send::<R, _>(&world, |cell| cell.try_read())) {
+            Poll::Ready(result) => Poll::Ready(result.map(|guard| NonSend { guard, marker : PhantomData })),
+            Poll::Pending       => Poll::Pending
+        }
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::of_immutable::<resource::marker::NonSend<R>>()
+    }
+
+}
+
+unsafe impl<R : 'static> ReadOnlyQuery for NonSend<&R> { }
+
+impl<R : 'static> Deref for NonSend<&R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The cell was stored under the `TypeId` of `R`.
+        unsafe{ self.guard.get_ref::<R>() }
+    }
+}
+
+impl<R : 'static> NonSend<&R> {
+
+    /// Extends the lifetime of this [`NonSend`] to `'static`.
+    pub fn as_static(self) -> NonSend<&'static R> {
+        NonSend { guard : self.guard, marker : PhantomData }
+    }
+
+}
+
+
+impl<R : 'static> NonSendInner for &mut R {
+    type Guard = RwLockWriteGuard<ResourceCell>;
+}
+
+unsafe impl<'l, R : 'static> Query for NonSend<&'l mut R> {
+    type Item = NonSend<&'l mut R>;
+    type State = ();
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+
+    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        match (acquire_non_send::<R, _>(&world, |cell| cell.try_write())) {
+            Poll::Ready(result) => Poll::Ready(result.map(|guard| NonSend { guard, marker : PhantomData })),
+            Poll::Pending       => Poll::Pending
+        }
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::of_mutable::<resource::marker::NonSend<R>>()
+    }
+
+}
+
+impl<R : 'static> Deref for NonSend<&mut R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The cell was stored under the `TypeId` of `R`.
+        unsafe{ self.guard.get_ref::<R>() }
+    }
+}
+
+impl<R : 'static> DerefMut for NonSend<&mut R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: The cell was stored under the `TypeId` of `R`, and is locked for writing.
+        unsafe{ self.guard.get_mut::<R>() }
+    }
+}
+
+impl<R : 'static> NonSend<&mut R> {
+
+    /// Extends the lifetime of this [`NonSend`] to `'static`.
+    pub fn as_static(self) -> NonSend<&'static mut R> {
+        NonSend { guard : self.guard, marker : PhantomData }
+    }
+
+}
+
+
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_storage_drops_on_any_thread() {
+        let storage = NonSendStorage::new();
+        assert!(thread::spawn(move || drop(storage)).join().is_ok());
+    }
+
+    #[test]
+    fn occupied_storage_panics_on_other_thread() {
+        let mut raw = RawNonSendStorage::new();
+        raw.insert(5_u32);
+        let storage = NonSendStorage::new_with(raw);
+        assert!(thread::spawn(move || drop(storage)).join().is_err());
+    }
+
+}
@@ -7,8 +7,10 @@ use crate::system::SystemId;
 use crate::query::{ Query, ReadOnlyQuery, QueryAcquireResult, QueryValidator };
 use crate::util::rwlock::{ RwLockReadGuard, RwLockWriteGuard };
 use core::ops::{ Deref, DerefMut };
-use core::any::{ TypeId, type_name };
+use core::any::type_name;
 use core::task::Poll;
+use core::marker::PhantomData;
+use core::mem;
 use alloc::sync::Arc;
 
 
@@ -25,35 +27,123 @@ mod __internal {
 use __internal::*;
 
 
-pub struct Res<R : ResInner> {
-    guard : R::Guard
+/// A [`Query`] for a [`Resource`] in a [`World`].
+///
+/// Use `Res<&R>` for immutable access, or `Res<&mut R>` for mutable access.
+///
+/// Several [`Resource`]s of the same type can exist at once by giving each a different key `K`.
+/// The key can be any `'static` type, and is usually an empty marker type. Un-keyed [`Resource`]s use `()`.
+/// Keyed [`Resource`]s are managed with the `_keyed` methods, such as [`World::insert_resource_keyed`].
+/// Initialising with [`FromWorld`](crate::world::FromWorld) and [`World::resource_scope`] only support un-keyed [`Resource`]s.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Connection {
+///     address : &'static str
+/// }
+///
+/// struct Primary;
+/// struct Replica;
+///
+/// let mut app = App::new();
+/// app.insert_resource_keyed::<_, Primary>(Connection { address : "10.0.0.1" });
+/// app.insert_resource_keyed::<_, Replica>(Connection { address : "10.0.0.2" });
+/// app.add_systems(Cycle, sync_replica);
+///
+/// async fn sync_replica(
+///     primary : Res<&Connection, Primary>,
+///     replica : Res<&Connection, Replica>
+/// ) {
+///     println!("Syncing {} to {}", primary.address, replica.address);
+/// }
+/// ```
+pub struct Res<R : ResInner, K : 'static = ()> {
+
+    /// The guard keeping the [`ResourceCell`] locked.
+    guard    : R::Guard,
+
+    /// The change tick that this query last ran at, before this run.
+    last_run : u64,
+
+    /// The change tick of this run.
+    this_run : u64,
+
+    /// [`PhantomData`] on the key.
+    marker   : PhantomData<fn() -> K>
+
+}
+unsafe impl<R : ResInner + Send, K : 'static> Send for Res<R, K> { }
+unsafe impl<R : ResInner + Sync, K : 'static> Sync for Res<R, K> { }
+
+
+impl<R : ResInner, K : 'static> Res<R, K>
+where R::Guard : Deref<Target = ResourceCell>
+{
+
+    /// Returns `true` if the [`Resource`] was inserted since the last time this query ran.
+    ///
+    /// The first time this query runs, every existing [`Resource`] is considered added.
+    pub fn is_added(&self) -> bool {
+        self.guard.added_tick() > self.last_run
+    }
+
+    /// Returns `true` if the [`Resource`] was inserted or accessed mutably since the last time this query ran.
+    ///
+    /// Accessing a [`Resource`] mutably through [`Res`] marks it as changed, even if the value is not modified.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    ///
+    /// #[derive(Resource)]
+    /// struct Config {
+    ///     scale : f32
+    /// }
+    ///
+    /// async fn rebuild_cache(
+    ///     config : Res<&Config>
+    /// ) {
+    ///     if (config.is_changed()) {
+    ///         println!("Rebuilding with scale {}", config.scale);
+    ///     }
+    /// }
+    /// ```
+    pub fn is_changed(&self) -> bool {
+        self.guard.changed_tick() > self.last_run
+    }
+
 }
-unsafe impl<R : ResInner + Send> Send for Res<R> { }
-unsafe impl<R : ResInner + Sync> Sync for Res<R> { }
 
 
 impl<R : Resource> ResInner for &R {
     type Guard = RwLockReadGuard<ResourceCell>;
 }
 
-unsafe impl<'l, R : Resource + 'static> Query for Res<&'l R> {
-    type Item = Res<&'l R>;
-    type State = ();
+unsafe impl<'l, R : Resource + 'static, K : 'static> Query for Res<&'l R, K> {
+    type Item = Res<&'l R, K>;
+    /// The change tick that this query last ran at.
+    type State = u64;
 
-    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { 0 }
 
-    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
         match (world.resources().try_read_raw()) {
             Poll::Ready(inner) => {
-                let type_id = TypeId::of::<R>();
-                match (inner.resources().find(|resource| resource.0 == type_id)) {
-                    Some((_, lock)) => match (lock.try_read()) {
-                        Poll::Ready(out) => Poll::Ready(QueryAcquireResult::Ready(Res { guard : out })),
-                        Poll::Pending    => Poll::Pending
+                match (inner.get::<R, K>()) {
+                    Some(lock) => match (lock.try_read()) {
+                        Poll::Ready(guard) => {
+                            let this_run = world.resources().increment_change_tick();
+                            let last_run = mem::replace(state, this_run);
+                            Poll::Ready(QueryAcquireResult::Ready(Res { guard, last_run, this_run, marker : PhantomData }))
+                        },
+                        Poll::Pending => Poll::Pending
                     },
                     None => Poll::Ready(QueryAcquireResult::DoesNotExist {
                         #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
-                        name : type_name::<resource::marker::Resource<R>>()
+                        name : type_name::<resource::marker::Resource<R, K>>()
                     })
                 }
             },
@@ -62,14 +152,14 @@ unsafe impl<'l, R : Resource + 'static> Query for Res<&'l R> {
     }
 
     fn validate() -> QueryValidator {
-        QueryValidator::of_immutable::<resource::marker::Resource<R>>()
+        QueryValidator::of_immutable::<resource::marker::Resource<R, K>>()
     }
 
 }
 
-unsafe impl<R : Resource + 'static> ReadOnlyQuery for Res<&R> { }
+unsafe impl<R : Resource + 'static, K : 'static> ReadOnlyQuery for Res<&R, K> { }
 
-impl<R : Resource> Deref for Res<&R> {
+impl<R : Resource, K : 'static> Deref for Res<&R, K> {
     type Target = R;
 
     fn deref(&self) -> &Self::Target {
@@ -78,9 +168,9 @@ impl<R : Resource> Deref for Res<&R> {
     }
 }
 
-impl<R : Resource> Res<&R> {
-    pub fn as_static(self) -> Res<&'static R> {
-        Res { guard : self.guard }
+impl<R : Resource, K : 'static> Res<&R, K> {
+    pub fn as_static(self) -> Res<&'static R, K> {
+        Res { guard : self.guard, last_run : self.last_run, this_run : self.this_run, marker : PhantomData }
     }
 }
 
@@ -89,24 +179,28 @@ impl<R : Resource> ResInner for &mut R {
     type Guard = RwLockWriteGuard<ResourceCell>;
 }
 
-unsafe impl<'l, R : Resource + 'static> Query for Res<&'l mut R> {
-    type Item = Res<&'l mut R>;
-    type State = ();
+unsafe impl<'l, R : Resource + 'static, K : 'static> Query for Res<&'l mut R, K> {
+    type Item = Res<&'l mut R, K>;
+    /// The change tick that this query last ran at.
+    type State = u64;
 
-    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { 0 }
 
-    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+    unsafe fn acquire(world : Arc<World>, state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
         match (world.resources().try_read_raw()) {
             Poll::Ready(inner) => {
-                let type_id = TypeId::of::<R>();
-                match (inner.resources().find(|resource| resource.0 == type_id)) {
-                    Some((_, lock)) => match (lock.try_write()) {
-                        Poll::Ready(out) => Poll::Ready(QueryAcquireResult::Ready(Res { guard : out })),
-                        Poll::Pending    => Poll::Pending
+                match (inner.get::<R, K>()) {
+                    Some(lock) => match (lock.try_write()) {
+                        Poll::Ready(guard) => {
+                            let this_run = world.resources().increment_change_tick();
+                            let last_run = mem::replace(state, this_run);
+                            Poll::Ready(QueryAcquireResult::Ready(Res { guard, last_run, this_run, marker : PhantomData }))
+                        },
+                        Poll::Pending => Poll::Pending
                     },
                     None => Poll::Ready(QueryAcquireResult::DoesNotExist {
                         #[cfg(any(debug_assertions, feature = "keep_debug_names"))]
-                        name : type_name::<resource::marker::Resource<R>>()
+                        name : type_name::<resource::marker::Resource<R, K>>()
                     })
                 }
             },
@@ -115,12 +209,12 @@ unsafe impl<'l, R : Resource + 'static> Query for Res<&'l mut R> {
     }
 
     fn validate() -> QueryValidator {
-        QueryValidator::of_immutable::<resource::marker::Resource<R>>()
+        QueryValidator::of_mutable::<resource::marker::Resource<R, K>>()
     }
 
 }
 
-impl<R : Resource> Deref for Res<&mut R> {
+impl<R : Resource, K : 'static> Deref for Res<&mut R, K> {
     type Target = R;
 
     fn deref(&self) -> &Self::Target {
@@ -129,15 +223,102 @@ impl<R : Resource> Deref for Res<&mut R> {
     }
 }
 
-impl<R : Resource> DerefMut for Res<&mut R> {
+impl<R : Resource, K : 'static> DerefMut for Res<&mut R, K> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.set_changed_tick(self.this_run);
         // SAFETY: TODO
         unsafe{ self.guard.get_mut::<R>() }
     }
 }
 
-impl<R : Resource> Res<&mut R> {
-    pub fn as_static(self) -> Res<&'static mut R> {
-        Res { guard : self.guard }
+impl<R : Resource, K : 'static> Res<&mut R, K> {
+    pub fn as_static(self) -> Res<&'static mut R, K> {
+        Res { guard : self.guard, last_run : self.last_run, this_run : self.this_run, marker : PhantomData }
+    }
+
+    /// Marks the [`Resource`] as changed, without accessing it mutably.
+    pub fn set_changed(&mut self) {
+        self.guard.set_changed_tick(self.this_run);
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+
+    struct Score(u32);
+    impl Resource for Score { }
+
+    struct Other;
+
+    #[test]
+    #[should_panic(expected = "Already mutably borrowed")]
+    fn mutable_res_conflicts_with_res() {
+        let world = Arc::new(World::new());
+        world.query_mut::<(Res<&mut Score>, Res<&Score>)>();
     }
+
+    #[test]
+    fn differently_keyed_res_do_not_conflict() {
+        let world = Arc::new(World::new());
+        world.query_mut::<(Res<&mut Score>, Res<&Score, Other>)>();
+    }
+
+    #[test]
+    fn keyed_res_are_separate_resources() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Score(1)).await;
+        world.insert_resource_keyed::<_, Other>(Score(2)).await;
+        {
+            let mut query = world.query::<(Res<&Score>, Res<&Score, Other>)>();
+            let (score, other) = query.acquire().await;
+            assert_eq!((score.0, other.0), (1, 2));
+        }
+        world.query_mut::<Res<&mut Score, Other>>().acquire().await.0 += 10;
+        assert_eq!(world.replace_resource_keyed::<_, Other>(Score(3)).await.map(|score| score.0), Some(12));
+        assert_eq!(world.take_resource_keyed::<Score, Other>().await.map(|score| score.0), Some(3));
+        assert!(world.query::<Option<Res<&Score, Other>>>().acquire().await.is_none());
+        assert_eq!(world.query::<Res<&Score>>().acquire().await.0, 1);
+    }); }
+
+    #[test]
+    fn change_ticks_track_insertion_and_mutable_access() { block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Score(0)).await;
+        let mut reader = world.query::<Res<&Score>>();
+        let mut writer = world.query_mut::<Res<&mut Score>>();
+
+        {
+            let score = reader.acquire().await;
+            assert!(score.is_added() && score.is_changed());
+        }
+        {
+            let score = reader.acquire().await;
+            assert!(! score.is_added() && ! score.is_changed());
+        }
+
+        // Acquiring mutably does not mark the resource as changed until it is accessed mutably.
+        drop(writer.acquire().await);
+        assert!(! reader.acquire().await.is_changed());
+
+        writer.acquire().await.0 += 1;
+        {
+            let score = reader.acquire().await;
+            assert!(! score.is_added() && score.is_changed());
+            assert_eq!(score.0, 1);
+        }
+        assert!(! reader.acquire().await.is_changed());
+
+        writer.acquire().await.set_changed();
+        assert!(reader.acquire().await.is_changed());
+
+        world.insert_resource(Score(5)).await;
+        let score = reader.acquire().await;
+        assert!(score.is_added() && score.is_changed());
+    }); }
+
 }
@@ -2,30 +2,96 @@
 
 
 use crate::resource::Resource;
+use crate::world::{ World, FromWorld };
 use crate::util::rwlock::{ RwLock, RwLockReadGuard, RwLockWriteGuard };
+use crate::util::stack::AtomicStack;
 use core::ops::{ Deref, DerefMut };
 use core::any::TypeId;
 use core::task::Poll;
 use core::alloc::Layout;
 use core::ptr::NonNull;
 use core::marker::PhantomData;
+use core::mem::{ self, ManuallyDrop };
+use core::sync::atomic::{ AtomicU64, Ordering as AtomicOrdering };
+use core::pin::Pin;
+use core::ops::AsyncFnOnce;
 use alloc::alloc::{ alloc, dealloc, handle_alloc_error };
-use alloc::collections::BTreeMap;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+use alloc::collections::{ BTreeMap, BTreeSet };
 
 
 /// TODO: Doc comments
 pub struct ResourceStorage {
 
     /// TODO: Doc comments
-    raw : RwLock<RawResourceStorage>
+    raw         : RwLock<RawResourceStorage>,
+
+    /// The current change tick, used to detect when [`Resource`]s are added or changed.
+    change_tick : AtomicU64,
+
+    /// The number of cycles that have been completed in the [`World`] that owns this storage. See [`RawResourceStorage::cycle_handle`].
+    cycle       : Arc<AtomicU64>,
+
+    /// Cells taken by [`ResourceStorage::scope`] which could not be reinserted because the storage was locked.
+    /// They are reinserted the next time the storage is locked.
+    deferred    : AtomicStack<(ResourceKey, RwLock<ResourceCell>)>
+
+}
+
+/// Identifies a [`Resource`] in a [`ResourceStorage`] by its type, and the type of its key.
+///
+/// Un-keyed [`Resource`]s use `()` as their key.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ResourceKey {
+
+    /// The [`TypeId`] of the [`Resource`].
+    type_id : TypeId,
+
+    /// The [`TypeId`] of the key.
+    key_id  : TypeId
+
+}
+
+impl ResourceKey {
+
+    /// Returns the [`ResourceKey`] of a [`Resource`] of type `R`, keyed by `K`.
+    pub fn of<R : 'static, K : 'static>() -> Self { Self {
+        type_id : TypeId::of::<R>(),
+        key_id  : TypeId::of::<K>()
+    } }
+
+    /// The [`TypeId`] of the [`Resource`].
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// The [`TypeId`] of the key.
+    pub fn key_id(&self) -> TypeId {
+        self.key_id
+    }
 
 }
 
+
+/// A function which constructs and inserts a [`Resource`] once the [`World`] exists. See [`RawResourceStorage::init`].
+pub type ResourceInitialiser = Box<dyn (FnOnce(Arc<World>) -> Pin<Box<dyn Future<Output = ()>>>) + Send + Sync>;
+
 /// TODO: Doc comments
 pub struct RawResourceStorage {
 
     /// TODO: Doc comments
-    resources : BTreeMap<TypeId, RwLock<ResourceCell>>
+    resources    : BTreeMap<ResourceKey, RwLock<ResourceCell>>,
+
+    /// Initialisers for [`Resource`]s which need a [`World`] to be constructed, in the order they were registered. See [`RawResourceStorage::init`].
+    initialisers : Vec<ResourceInitialiser>,
+
+    /// The keys of the [`Resource`]s in `initialisers`.
+    initialised  : BTreeSet<ResourceKey>,
+
+    /// The number of cycles that have been completed in the [`World`] that these resources are given to.
+    cycle        : Arc<AtomicU64>
 
 }
 
@@ -33,19 +99,72 @@ pub struct RawResourceStorage {
 impl ResourceStorage {
 
     /// TODO: Doc comment
+    ///
+    /// Initialisers in `raw` which have not been taken are kept, and run by [`World::run_resource_initialisers`].
     pub fn new_with(raw : RawResourceStorage) -> Self { Self {
-        raw : RwLock::new(raw)
+        cycle       : Arc::clone(&raw.cycle),
+        raw         : RwLock::new(raw),
+        change_tick : AtomicU64::new(ResourceCell::INITIAL_TICK),
+        deferred    : AtomicStack::new()
     } }
 
+    /// Returns a handle to the number of cycles that have been completed in the [`World`] that owns this storage. See [`World::cycle`].
+    pub(crate) fn cycle_handle(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.cycle)
+    }
+
+
+    /// Returns the current change tick.
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Advances the change tick, returning the new value.
+    pub fn increment_change_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, AtomicOrdering::Relaxed) + 1
+    }
+
 
     /// Attempts to acquire a read lock to the raw data, returning immediately if it can't.
     pub fn try_read_raw(&self) -> Poll<RwLockReadGuard<RawResourceStorage>> {
-        self.raw.try_read()
+        if (self.deferred.is_empty()) {
+            self.raw.try_read()
+        } else {
+            self.try_write_raw().map(RwLockWriteGuard::downgrade)
+        }
     }
 
     /// Acquires a read lock to the raw data.
     pub async fn read_raw(&self) -> RwLockReadGuard<RawResourceStorage> {
-        self.raw.read().await
+        if (self.deferred.is_empty()) {
+            self.raw.read().await
+        } else {
+            RwLockWriteGuard::downgrade(self.write_raw().await)
+        }
+    }
+
+    /// Attempts to acquire a write lock to the raw data, returning immediately if it can't.
+    pub fn try_write_raw(&self) -> Poll<RwLockWriteGuard<RawResourceStorage>> {
+        self.raw.try_write().map(|mut raw| { self.reinsert_deferred(&mut raw); raw })
+    }
+
+    /// Acquires a write lock to the raw data.
+    pub async fn write_raw(&self) -> RwLockWriteGuard<RawResourceStorage> {
+        let mut raw = self.raw.write().await;
+        self.reinsert_deferred(&mut raw);
+        raw
+    }
+
+    /// Reinserts the cells which [`ResourceScopeGuard`] could not reinsert, because the storage was locked.
+    fn reinsert_deferred(&self, raw : &mut RawResourceStorage) {
+        for (key, lock) in self.deferred.take() {
+            raw.resources.insert(key, lock);
+        }
+    }
+
+    /// Removes the initialisers registered with [`RawResourceStorage::init`] which have not been taken yet, returning them in the order they were registered.
+    pub async fn take_initialisers(&self) -> Vec<ResourceInitialiser> {
+        self.write_raw().await.take_initialisers()
     }
 
 }
@@ -54,17 +173,63 @@ impl RawResourceStorage {
 
     /// Creates an empty [`RawResourceStorage`].
     pub fn new() -> Self { Self {
-        resources : BTreeMap::new()
+        resources    : BTreeMap::new(),
+        initialisers : Vec::new(),
+        initialised  : BTreeSet::new(),
+        cycle        : Arc::new(AtomicU64::new(0))
     } }
 
+    /// Returns a handle to the number of cycles that have been completed in the [`World`] that these resources are given to.
+    ///
+    /// This lets things created before the [`World`] exists, such as [`EventSender`](crate::query::EventSender)s, read its cycle. See [`World::cycle`].
+    pub(crate) fn cycle_handle(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.cycle)
+    }
+
     /// Returns an [`Iterator`] over [`RwLock`] wrapped [`ResourceCell`]s.
-    pub fn resources(&self) -> impl Iterator<Item = (TypeId, &RwLock<ResourceCell>)> {
-        self.resources.iter().map(|(type_id, resource)| (*type_id, resource))
+    pub fn resources(&self) -> impl Iterator<Item = (ResourceKey, &RwLock<ResourceCell>)> {
+        self.resources.iter().map(|(key, resource)| (*key, resource))
+    }
+
+    /// Returns the [`RwLock`] wrapped [`ResourceCell`] of a [`Resource`] of type `R` keyed by `K`, if it exists.
+    pub fn get<R : Resource + 'static, K : 'static>(&self) -> Option<&RwLock<ResourceCell>> {
+        self.resources.get(&ResourceKey::of::<R, K>())
     }
 
     /// TODO: Doc comments
     pub fn insert<R : Resource + 'static>(&mut self, resource : R) -> bool {
-        self.resources.try_insert(TypeId::of::<R>(), RwLock::new(ResourceCell::new(resource))).is_ok()
+        self.insert_keyed::<R, ()>(resource)
+    }
+
+    /// Inserts a [`Resource`] keyed by `K`.
+    ///
+    /// Returns `false` if a [`Resource`] of the same type and key has already been inserted.
+    pub fn insert_keyed<R : Resource + 'static, K : 'static>(&mut self, resource : R) -> bool {
+        self.resources.try_insert(ResourceKey::of::<R, K>(), RwLock::new(ResourceCell::new(resource))).is_ok()
+    }
+
+    /// Registers a [`Resource`] to be constructed with [`FromWorld`] once the [`World`] exists.
+    ///
+    /// If a [`Resource`] of the same type is inserted before then, it is kept and the initialiser does nothing.
+    /// Initialisers run in the order they were registered, so a [`FromWorld`] implementation can read resources registered before it.
+    /// The initialisers must be run by the app runner. See [`World::run_resource_initialisers`].
+    /// Only un-keyed [`Resource`]s can be initialised.
+    pub fn init<R : Resource + FromWorld + 'static>(&mut self) {
+        let key = ResourceKey::of::<R, ()>();
+        if (! self.resources.contains_key(&key) && self.initialised.insert(key)) {
+            self.initialisers.push(Box::new(|world|
+                Box::pin(async move { world.init_resource::<R>().await })
+            ));
+        }
+    }
+
+    /// Removes the initialisers registered with [`RawResourceStorage::init`], returning them in the order they were registered.
+    ///
+    /// Each should be called in order with the [`World`] after it has been created.
+    /// Initialisers which are not taken are kept when the storage is given to [`ResourceStorage::new_with`]. See [`World::run_resource_initialisers`].
+    pub fn take_initialisers(&mut self) -> Vec<ResourceInitialiser> {
+        self.initialised.clear();
+        mem::take(&mut self.initialisers)
     }
 
 }
@@ -72,40 +237,114 @@ impl RawResourceStorage {
 impl ResourceStorage {
 
     /// Creates an empty [`ResourceStorage`].
-    pub fn new() -> Self { Self {
-        raw : RwLock::new(RawResourceStorage {
-            resources : BTreeMap::new()
-        })
-    } }
+    pub fn new() -> Self {
+        Self::new_with(RawResourceStorage::new())
+    }
 
     /// TODO: Doc comments
     pub async fn insert<R : Resource + 'static>(&self, resource : R) {
-        self.raw.write().await.resources.insert(TypeId::of::<R>(), RwLock::new(ResourceCell::new(resource)));
+        self.insert_keyed::<R, ()>(resource).await
+    }
+
+    /// Inserts a [`Resource`] keyed by `K`, overwriting any previous resource of the same type and key.
+    pub async fn insert_keyed<R : Resource + 'static, K : 'static>(&self, resource : R) {
+        let mut raw = self.write_raw().await;
+        raw.resources.insert(ResourceKey::of::<R, K>(), RwLock::new(ResourceCell::new_with_tick(resource, self.increment_change_tick())));
     }
 
     /// TODO: Doc comments
     pub async fn replace<R : Resource + 'static>(&self, resource : R) -> Option<R> {
-        let lock = self.raw.write().await.resources.insert(TypeId::of::<R>(), RwLock::new(ResourceCell::new(resource)))?;
+        self.replace_keyed::<R, ()>(resource).await
+    }
+
+    /// Inserts a [`Resource`] keyed by `K`, returning the old resource of the same type and key if it existed.
+    pub async fn replace_keyed<R : Resource + 'static, K : 'static>(&self, resource : R) -> Option<R> {
+        let mut raw = self.write_raw().await;
+        let lock    = raw.resources.insert(ResourceKey::of::<R, K>(), RwLock::new(ResourceCell::new_with_tick(resource, self.increment_change_tick())))?;
+        drop(raw);
         // SAFETY: TODO
         Some(unsafe{ lock.into_inner().await.read() })
     }
 
+    /// Inserts a [`Resource`] only if one of the same type does not already exist.
+    ///
+    /// Returns `false` if the [`Resource`] already existed, in which case the given value is dropped.
+    /// Only un-keyed [`Resource`]s are checked.
+    pub async fn try_insert<R : Resource + 'static>(&self, resource : R) -> bool {
+        let mut raw = self.write_raw().await;
+        let key     = ResourceKey::of::<R, ()>();
+        if (raw.resources.contains_key(&key)) { return false; }
+        raw.resources.insert(key, RwLock::new(ResourceCell::new_with_tick(resource, self.increment_change_tick())));
+        true
+    }
+
+    /// Returns `true` if a [`Resource`] of type `R` exists.
+    pub async fn contains<R : Resource + 'static>(&self) -> bool {
+        self.contains_keyed::<R, ()>().await
+    }
+
+    /// Returns `true` if a [`Resource`] of type `R` keyed by `K` exists.
+    pub async fn contains_keyed<R : Resource + 'static, K : 'static>(&self) -> bool {
+        self.read_raw().await.resources.contains_key(&ResourceKey::of::<R, K>())
+    }
+
     /// TODO: Doc comments
     pub async fn remove<R : Resource + 'static>(&self) {
-        self.raw.write().await.resources.remove(&TypeId::of::<R>());
+        self.remove_keyed::<R, ()>().await
+    }
+
+    /// Removes a [`Resource`] keyed by `K`.
+    pub async fn remove_keyed<R : Resource + 'static, K : 'static>(&self) {
+        self.write_raw().await.resources.remove(&ResourceKey::of::<R, K>());
     }
 
     /// TODO: Doc comments
     pub async fn take<R : Resource + 'static>(&self) -> Option<R> {
-        let lock = self.raw.write().await.resources.remove(&TypeId::of::<R>())?;
+        self.take_keyed::<R, ()>().await
+    }
+
+    /// Removes a [`Resource`] keyed by `K`, returning it if it existed.
+    pub async fn take_keyed<R : Resource + 'static, K : 'static>(&self) -> Option<R> {
+        let lock = self.write_raw().await.resources.remove(&ResourceKey::of::<R, K>())?;
         // SAFETY: TODO
         Some(unsafe{ lock.into_inner().await.read() })
     }
 
+    /// Temporarily removes a [`Resource`] from this storage, calling `f` with it, and then reinserting it.
+    ///
+    /// If `f` panics or the returned future is dropped early, the [`Resource`] is still reinserted.
+    /// If the storage is locked at that point, reinsertion is deferred until the next time the storage is locked.
+    /// If a [`Resource`] of the same type was inserted while `f` was running, it is overwritten.
+    ///
+    /// Returns `None` if the [`Resource`] does not exist. Only un-keyed [`Resource`]s can be scoped.
+    pub async fn scope<R : Resource + 'static, T>(&self, world : &Arc<World>, f : impl AsyncFnOnce(&Arc<World>, &mut R) -> T) -> Option<T> {
+        let key  = ResourceKey::of::<R, ()>();
+        let lock = self.write_raw().await.resources.remove(&key)?;
+        let mut guard = ResourceScopeGuard {
+            storage : self,
+            key,
+            cell    : Some(lock.into_inner().await)
+        };
+        // SAFETY: `guard.cell` is always `Some` until the guard is dropped.
+        let cell = unsafe{ guard.cell.as_mut().unwrap_unchecked() };
+        // SAFETY: The cell was stored under the `TypeId` of `R`.
+        let out = f(world, unsafe{ cell.get_mut::<R>() }).await;
+        cell.set_changed_tick(self.increment_change_tick());
+        let mut raw = self.write_raw().await;
+        // SAFETY: `guard.cell` is always `Some` until the guard is dropped.
+        raw.resources.insert(key, RwLock::new(unsafe{ guard.cell.take().unwrap_unchecked() }));
+        Some(out)
+    }
+
     /// Acquires a read lock to a [`ResourceCell`] by [`Resource`] type, if it exists.
     pub async fn get_ref<R : Resource + 'static>(&self) -> Option<ResourceCellReadGuard<'_, R>> {
-        let raw = self.raw.read().await;
-        let lock = raw.resources.get(&TypeId::of::<R>())?;
+        self.get_ref_keyed::<R, ()>().await
+    }
+
+    /// Acquires a read lock to a [`ResourceCell`] by [`Resource`] type and key, if it exists.
+    pub async fn get_ref_keyed<R : Resource + 'static, K : 'static>(&self) -> Option<ResourceCellReadGuard<'_, R>> {
+        let raw = self.read_raw().await;
+        let lock = raw.resources.get(&ResourceKey::of::<R, K>())?;
         Some(ResourceCellReadGuard {
             guard  : lock.read().await,
             marker : PhantomData
@@ -114,30 +353,41 @@ impl ResourceStorage {
 
     /// Acquires a write lock to a [`ResourceCell`] by [`Resource`] type, if it exists.
     pub async fn get_mut<R : Resource + 'static>(&self) -> Option<ResourceCellWriteGuard<'_, R>> {
-        let raw = self.raw.read().await;
-        let lock = raw.resources.get(&TypeId::of::<R>())?;
+        self.get_mut_keyed::<R, ()>().await
+    }
+
+    /// Acquires a write lock to a [`ResourceCell`] by [`Resource`] type and key, if it exists.
+    pub async fn get_mut_keyed<R : Resource + 'static, K : 'static>(&self) -> Option<ResourceCellWriteGuard<'_, R>> {
+        let raw = self.read_raw().await;
+        let lock = raw.resources.get(&ResourceKey::of::<R, K>())?;
+        let guard = lock.write().await;
         Some(ResourceCellWriteGuard {
-            guard  : lock.write().await,
-            marker : PhantomData
+            guard,
+            this_run : self.increment_change_tick(),
+            marker   : PhantomData
         })
     }
 
-    /// Acquires a write lock to a [`ResourceCell`] by [`Resource`] type, creating it if needed.
+    /// Acquires a write lock to an un-keyed [`ResourceCell`] by [`Resource`] type, creating it if needed.
     pub async fn get_mut_or_insert<R : Resource + 'static>(&self, f : impl FnOnce() -> R) -> ResourceCellWriteGuard<'_, R> {
-        let mut raw = self.raw.write().await;
-        let type_id = TypeId::of::<R>();
-        if let Some(lock) = raw.resources.get(&type_id) {
+        let mut raw = self.write_raw().await;
+        let key     = ResourceKey::of::<R, ()>();
+        if let Some(lock) = raw.resources.get(&key) {
+            let guard = lock.write().await;
             ResourceCellWriteGuard {
-                guard  : lock.write().await,
-                marker : PhantomData
+                guard,
+                this_run : self.increment_change_tick(),
+                marker   : PhantomData
             }
         } else {
-            let lock = unsafe{ RwLock::new_writing(ResourceCell::new(f())) };
-            let guard = ResourceCellWriteGuard {
-                guard  : unsafe{ lock.write_unchecked() },
-                marker : PhantomData
+            let this_run = self.increment_change_tick();
+            let lock     = unsafe{ RwLock::new_writing(ResourceCell::new_with_tick(f(), this_run)) };
+            let guard    = ResourceCellWriteGuard {
+                guard    : unsafe{ lock.write_unchecked() },
+                this_run,
+                marker   : PhantomData
             };
-            raw.resources.insert(type_id, lock);
+            raw.resources.insert(key, lock);
             guard
         }
     }
@@ -145,6 +395,37 @@ impl ResourceStorage {
 }
 
 
+/// Reinserts a [`ResourceCell`] taken by [`ResourceStorage::scope`] if the scope is interrupted.
+struct ResourceScopeGuard<'l> {
+
+    /// The storage to reinsert the cell into.
+    storage : &'l ResourceStorage,
+
+    /// The [`ResourceKey`] that the cell was stored under.
+    key     : ResourceKey,
+
+    /// The taken cell, or `None` if it has already been reinserted.
+    cell    : Option<ResourceCell>
+
+}
+
+impl Drop for ResourceScopeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(mut cell) = self.cell.take() {
+            cell.set_changed_tick(self.storage.increment_change_tick());
+            let key  = self.key;
+            let lock = RwLock::new(cell);
+            // The storage lock can not be awaited from here, and may be held by the task that dropped this guard.
+            //  If it is locked, the cell is reinserted by whichever task locks the storage next.
+            match (self.storage.try_write_raw()) {
+                Poll::Ready(mut raw) => { raw.resources.insert(key, lock); },
+                Poll::Pending        => { self.storage.deferred.push((key, lock)); }
+            }
+        }
+    }
+}
+
+
 /// TODO: Doc comments
 pub struct ResourceCellReadGuard<'l, R : Resource> {
 
@@ -170,10 +451,13 @@ impl<'l, R : Resource> Deref for ResourceCellReadGuard<'l, R> {
 pub struct ResourceCellWriteGuard<'l, R : Resource> {
 
     /// TODO: Doc comments
-    guard  : RwLockWriteGuard<ResourceCell>,
+    guard    : RwLockWriteGuard<ResourceCell>,
+
+    /// The change tick that the cell is marked with when accessed mutably.
+    this_run : u64,
 
     /// TODO: Doc comments
-    marker : PhantomData<&'l R>
+    marker   : PhantomData<&'l R>
 
 }
 
@@ -188,6 +472,7 @@ impl<'l, R : Resource> Deref for ResourceCellWriteGuard<'l, R> {
 
 impl<'l, R : Resource> DerefMut for ResourceCellWriteGuard<'l, R> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.set_changed_tick(self.this_run);
         // SAFETY: TODO
         unsafe{ self.guard.get_mut::<R>() }
     }
@@ -198,20 +483,36 @@ impl<'l, R : Resource> DerefMut for ResourceCellWriteGuard<'l, R> {
 pub struct ResourceCell {
 
     /// TODO: Doc comments
-    layout   : Layout,
+    layout       : Layout,
 
     /// A pointer to the contained value.
-    data_ptr : NonNull<u8>,
+    data_ptr     : NonNull<u8>,
 
     /// TODO: Doc comments
-    drop     : unsafe fn(NonNull<u8>) -> ()
+    drop         : unsafe fn(NonNull<u8>) -> (),
+
+    /// The change tick that the value was inserted at.
+    added_tick   : u64,
+
+    /// The change tick that the value was last accessed mutably at.
+    changed_tick : u64
 
 }
 
 impl ResourceCell {
 
-    /// Creates a new cell with the given [`Resource`] type.
-    pub fn new<R : Resource>(resource : R) -> Self {
+    /// The change tick that cells created before a [`ResourceStorage`] exists are marked with.
+    ///
+    /// Queries start out having last run before this tick, so these cells are considered added and changed the first time they are seen.
+    pub const INITIAL_TICK : u64 = 1;
+
+    /// Creates a new cell with the given [`Resource`] type, marked with [`ResourceCell::INITIAL_TICK`].
+    pub fn new<R>(resource : R) -> Self {
+        Self::new_with_tick(resource, Self::INITIAL_TICK)
+    }
+
+    /// Creates a new cell with the given [`Resource`] type, marked as added and changed at `tick`.
+    pub fn new_with_tick<R>(resource : R, tick : u64) -> Self {
         let layout = Layout::new::<R>();
         let data_ptr = unsafe{ alloc(layout) };
         if (data_ptr.is_null()) {
@@ -222,19 +523,36 @@ impl ResourceCell {
         Self {
             layout,
             // SAFETY: An alloc error was emitted above if `data_ptr` `is_null`.
-            data_ptr : unsafe{ NonNull::new_unchecked(data_ptr) },
+            data_ptr     : unsafe{ NonNull::new_unchecked(data_ptr) },
             // SAFETY: TODO
-            drop     : |data_ptr| { unsafe{ data_ptr.cast::<R>().drop_in_place(); } }
+            drop         : |data_ptr| { unsafe{ data_ptr.cast::<R>().drop_in_place(); } },
+            added_tick   : tick,
+            changed_tick : tick
         }
     }
 
+    /// Returns the change tick that the value was inserted at.
+    pub fn added_tick(&self) -> u64 {
+        self.added_tick
+    }
+
+    /// Returns the change tick that the value was last accessed mutably at.
+    pub fn changed_tick(&self) -> u64 {
+        self.changed_tick
+    }
+
+    /// Marks the value as changed at `tick`.
+    pub fn set_changed_tick(&mut self, tick : u64) {
+        self.changed_tick = tick;
+    }
+
     /// Returns a reference to the value in the cell.
     ///
     /// # Safety
     /// The caller is responsible for ensuring that
     /// - the cell **is occupied**.
     /// - `R` is the type stored in this cell.
-    pub unsafe fn get_ref<R : Resource>(&self) -> &R {
+    pub unsafe fn get_ref<R>(&self) -> &R {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
         unsafe{ self.data_ptr.cast::<R>().as_ref() }
     }
@@ -245,7 +563,7 @@ impl ResourceCell {
     /// The caller is responsible for ensuring that
     /// - the cell **is occupied**.
     /// - `R` is the type stored in this cell.
-    pub unsafe fn get_mut<R : Resource>(&mut self) -> &mut R {
+    pub unsafe fn get_mut<R>(&mut self) -> &mut R {
         // SAFETY: The caller is responsible for upholding the safety guarantees.
         unsafe{ self.data_ptr.cast::<R>().as_mut() }
     }
@@ -258,14 +576,24 @@ impl ResourceCell {
     /// - `R` is the type stored in this cell.
     /// - the pointer is not used when the cell is unoccupied or has been dropped.
     /// - data-races are prevented.
-    pub unsafe fn get_ptr<R : Resource>(&self) -> *mut R {
+    pub unsafe fn get_ptr<R>(&self) -> *mut R {
         self.data_ptr.cast::<R>().as_ptr()
     }
 
-    /// TODO: Doc comment
-    pub unsafe fn read<R : Resource>(self) -> R {
-        // SAFETY: TODO
-        unsafe{ self.data_ptr.cast::<R>().read() }
+    /// Reads the value out of the cell, and deallocates the cell without dropping the value.
+    ///
+    /// # Safety
+    /// The caller is responsible for ensuring that
+    /// - the cell **is occupied**.
+    /// - `R` is the type stored in this cell.
+    pub unsafe fn read<R>(self) -> R {
+        let cell = ManuallyDrop::new(self);
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        let value = unsafe{ cell.data_ptr.cast::<R>().read() };
+        // SAFETY: `cell.data_ptr` was allocated with `cell.layout`, and the value was moved out above.
+        //         `cell` is never dropped, so the value will not be dropped a second time.
+        unsafe{ dealloc(cell.data_ptr.as_ptr(), cell.layout) }
+        value
     }
 
 }
@@ -279,3 +607,137 @@ impl Drop for ResourceCell {
         unsafe{ dealloc(self.data_ptr.as_ptr(), self.layout) }
     }
 }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+
+    static DROPS : AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted(u32);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, AtomicOrdering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn read_moves_value_out_of_cell() {
+        let cell  = ResourceCell::new(Counted(7));
+        // SAFETY: The cell holds a `Counted`.
+        let value = unsafe{ cell.read::<Counted>() };
+        assert_eq!(DROPS.load(AtomicOrdering::Relaxed), 0);
+        assert_eq!(value.0, 7);
+        drop(value);
+        assert_eq!(DROPS.load(AtomicOrdering::Relaxed), 1);
+    }
+
+    #[derive(Default)]
+    struct Base(u32);
+    impl Resource for Base { }
+
+    macro_rules! link { ($name:ident, $prev:ident) => {
+        struct $name(u32);
+        impl Resource for $name { }
+        impl FromWorld for $name {
+            async fn from_world(world : &Arc<World>) -> Self {
+                Self(world.resources().get_ref::<$prev>().await.map_or(0, |prev| prev.0 + 1))
+            }
+        }
+    } }
+    link!(LinkA, Base);
+    link!(LinkB, LinkA);
+    link!(LinkC, LinkB);
+    link!(LinkD, LinkC);
+
+    #[test]
+    fn initialisers_run_in_registration_order() { async_std::task::block_on(async {
+        let mut raw = RawResourceStorage::new();
+        raw.init::<Base>();
+        raw.init::<LinkA>();
+        raw.init::<LinkB>();
+        raw.init::<LinkC>();
+        raw.init::<LinkD>();
+        raw.init::<LinkA>();
+        let initialisers = raw.take_initialisers();
+        assert_eq!(initialisers.len(), 5);
+
+        let world = Arc::new(World::new_with(ResourceStorage::new_with(raw)));
+        for initialiser in initialisers {
+            initialiser(Arc::clone(&world)).await;
+        }
+        assert_eq!(world.resources().get_ref::<LinkD>().await.unwrap().0, 4);
+    }) }
+
+    /// Polls `future` once, expecting it to be pending, and returns it.
+    #[cfg(not(feature = "no_std"))]
+    fn poll_once<F : Future>(future : F) -> Pin<Box<F>> {
+        let mut future = Box::pin(future);
+        let mut ctx    = core::task::Context::from_waker(core::task::Waker::noop());
+        assert!(future.as_mut().poll(&mut ctx).is_pending());
+        future
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn scope_reinserts_after_panic() { async_std::task::block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Base(1)).await;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| async_std::task::block_on(
+            world.resource_scope(async |_, base : &mut Base| {
+                base.0 += 1;
+                panic!("scope panicked");
+            })
+        )));
+        assert!(result.is_err());
+        assert_eq!(world.resources().get_ref::<Base>().await.unwrap().0, 2);
+    }) }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn scope_reinserts_after_cancel() { async_std::task::block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Base(1)).await;
+        drop(poll_once(world.resource_scope(async |_, base : &mut Base| {
+            base.0 += 1;
+            core::future::pending::<()>().await;
+        })));
+        assert_eq!(world.resources().get_ref::<Base>().await.unwrap().0, 2);
+    }) }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn scope_defers_reinsertion_while_storage_is_locked() { async_std::task::block_on(async {
+        let world = Arc::new(World::new());
+        world.insert_resource(Base(1)).await;
+        let scope = poll_once(world.resource_scope(async |_, base : &mut Base| {
+            base.0 += 1;
+            core::future::pending::<()>().await;
+        }));
+        // Hold the storage lock while the scope is dropped.
+        let raw = world.resources().read_raw().await;
+        drop(scope);
+        assert!(! raw.resources.contains_key(&ResourceKey::of::<Base, ()>()));
+        drop(raw);
+        // The next lock of the storage reinserts the resource.
+        assert!(world.resources().contains::<Base>().await);
+        assert_eq!(world.resources().get_ref::<Base>().await.unwrap().0, 2);
+        assert!(world.cmd_queue.try_write().unwrap().is_empty());
+    }) }
+
+    #[test]
+    fn untaken_initialisers_run_with_the_world() { async_std::task::block_on(async {
+        let mut raw = RawResourceStorage::new();
+        raw.init::<Base>();
+        raw.init::<LinkA>();
+        let world = Arc::new(World::new_with(ResourceStorage::new_with(raw)));
+        assert!(! world.resources().contains::<LinkA>().await);
+        world.run_resource_initialisers().await;
+        assert_eq!(world.resources().get_ref::<LinkA>().await.unwrap().0, 1);
+        assert!(world.resources().take_initialisers().await.is_empty());
+    }) }
+
+}
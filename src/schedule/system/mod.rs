@@ -37,7 +37,7 @@ unsafe impl<S : System<Return, Passed = Passed>, Passed : SystemPassable, Return
 
 unsafe impl<S : System<(), Passed = ()>, C : System<bool, Passed = ()>> TypeErasedSystem<(), ()> for ScheduledSystemConfig<S, C> {
     unsafe fn acquire_and_run<'l>(&'l mut self, _passed : (), world : Arc<World>) -> Pin<Box<dyn Future<Output = ()> + 'l>> {
-        Box::pin(async {
+        Box::pin(async move {
             if (! self.depends_ids.is_empty()) {
                 'wait_for_dependencies : loop {
                     if (world.is_exiting()) { return; }
@@ -55,14 +55,28 @@ unsafe impl<S : System<(), Passed = ()>, C : System<bool, Passed = ()>> TypeEras
                     task::yield_now().await;
                 }
             }
-            if let Some(run_if) = &mut self.run_if {
+            let system = self.run.get_mut(Arc::clone(&world));
+            let run_if = self.run_if.as_mut().map(|run_if| run_if.run.get_mut(Arc::clone(&world)));
+            let exclusive = system.is_exclusive() || run_if.as_ref().is_some_and(|run_if| run_if.is_exclusive());
+            let locks     = system.holds_world_locks() || run_if.as_ref().is_some_and(|run_if| run_if.holds_world_locks());
+            // Systems with exclusive access wait for every other system holding world locks to finish, and those systems wait for them.
+            // The lock is held for the whole run, as the values a system acquired stay locked until it finishes. Waiting exclusive
+            //  systems are preferred, so systems which would hold world locks are not started while one is waiting. See `WorldMut`.
+            let (_read, _write) = if (exclusive) {
+                (None, Some(world.exclusive.write().await))
+            } else if (locks) {
+                (Some(world.exclusive.read().await), None)
+            } else {
+                (None, None)
+            };
+            if let Some(run_if) = run_if {
                 // SAFETY: TODO
-                if (! unsafe{ run_if.run.get_mut(Arc::clone(&world)).acquire_and_run((), Arc::clone(&world)) }.await) {
+                if (! unsafe{ run_if.acquire_and_run((), Arc::clone(&world)) }.await) {
                     return;
                 }
             }
             // SAFETY: TODO
-            unsafe{ self.run.get_mut(Arc::clone(&world)).acquire_and_run((), world) }.await
+            unsafe{ system.acquire_and_run((), Arc::clone(&world)) }.await
         })
     }
 }
@@ -10,6 +10,7 @@ use core::any::{ TypeId, type_name };
 use core::ops::AsyncFnMut;
 use core::marker::PhantomData;
 use core::mem;
+use alloc::vec::Vec;
 use alloc::sync::Arc;
 use alloc::collections::BTreeSet;
 
@@ -37,23 +38,29 @@ macro impl_into_system_for_f( $( #[$meta:meta] )* $( $generic:ident ),* $(,)? )
 
         #[track_caller]
         fn into_system(self, world : Arc<World>, system_id : Option<SystemId>) -> Self::System {
-            <( $( $generic , )* )>::validate().panic_on_violation();
+            let validator = <( $( $generic , )* )>::validate();
+            validator.panic_on_violation();
             $( let $generic = <$generic as Query>::init_state(Arc::clone(&world), system_id); )*
             FunctionSystem {
                 source       : type_name::<F>(),
                 function     : self,
                 query_states : ( $( $generic , )* ),
+                exclusive    : validator.is_exclusive(),
+                locks        : ! validator.is_empty(),
                 marker       : PhantomData
             }
         }
 
         #[track_caller]
         unsafe fn into_system_unchecked(self, world : Arc<World>, system_id : Option<SystemId>) -> Self::System {
+            let validator = <( $( $generic , )* )>::validate();
             $( let $generic = <$generic as Query>::init_state(Arc::clone(&world), system_id); )*
             FunctionSystem {
                 source       : type_name::<F>(),
                 function     : self,
                 query_states : ( $( $generic , )* ),
+                exclusive    : validator.is_exclusive(),
+                locks        : ! validator.is_empty(),
                 marker       : PhantomData
             }
         }
@@ -91,23 +98,29 @@ macro impl_into_system_for_f( $( #[$meta:meta] )* $( $generic:ident ),* $(,)? )
 
         #[track_caller]
         fn into_system(self, world : Arc<World>, system_id : Option<SystemId>) -> Self::System {
-            <( $( $generic , )* )>::validate().panic_on_violation();
+            let validator = <( $( $generic , )* )>::validate();
+            validator.panic_on_violation();
             $( let $generic = <$generic as Query>::init_state(Arc::clone(&world), system_id); )*
             FunctionSystem {
                 source       : type_name::<F>(),
                 function     : self,
                 query_states : ( $( $generic , )* ),
+                exclusive    : validator.is_exclusive(),
+                locks        : ! validator.is_empty(),
                 marker       : PhantomData
             }
         }
 
         #[track_caller]
         unsafe fn into_system_unchecked(self, world : Arc<World>, system_id : Option<SystemId>) -> Self::System {
+            let validator = <( $( $generic , )* )>::validate();
             $( let $generic = <$generic as Query>::init_state(Arc::clone(&world), system_id); )*
             FunctionSystem {
                 source       : type_name::<F>(),
                 function     : self,
                 query_states : ( $( $generic , )* ),
+                exclusive    : validator.is_exclusive(),
+                locks        : ! validator.is_empty(),
                 marker       : PhantomData
             }
         }
@@ -139,6 +152,12 @@ pub struct FunctionSystem<F, Passed, Q, Params, Return> {
     /// TODO: Doc comment
     query_states : Q,
 
+    /// Whether the queries request exclusive access to the entire [`World`].
+    exclusive    : bool,
+
+    /// Whether the queries hold locks on any values in the [`World`].
+    locks        : bool,
+
     /// TODO: Doc comment
     marker       : PhantomData<fn(Passed, Params) -> Return>
 
@@ -186,6 +205,14 @@ macro impl_system_for_function_system( $( #[$meta:meta] )* $( $generic:ident ),*
             world.ran_systems.write().await.insert(TypeId::of::<F>());
             out
         }
+
+        fn is_exclusive(&self) -> bool {
+            self.exclusive
+        }
+
+        fn holds_world_locks(&self) -> bool {
+            self.locks
+        }
     }
 
     $( #[ $meta ] )*
@@ -239,6 +266,14 @@ macro impl_system_for_function_system( $( #[$meta:meta] )* $( $generic:ident ),*
             world.ran_systems.write().await.insert(TypeId::of::<F>());
             out
         }
+
+        fn is_exclusive(&self) -> bool {
+            self.exclusive
+        }
+
+        fn holds_world_locks(&self) -> bool {
+            self.locks
+        }
     }
 
     $( #[ $meta ] )*
@@ -108,6 +108,14 @@ where   A : System<BPassed, Passed = APassed>,
         let b_passed = unsafe{ self.a.acquire_and_run(a_passed, world) }.await;
         (self.b)(b_passed)
     }
+
+    fn is_exclusive(&self) -> bool {
+        self.a.is_exclusive()
+    }
+
+    fn holds_world_locks(&self) -> bool {
+        self.a.holds_world_locks()
+    }
 }
 
 unsafe impl<APassed, A, BPassed, B, Return>
@@ -56,6 +56,20 @@ pub trait System<Return> {
     /// TODO: Doc comment
     async unsafe fn acquire_and_run(&mut self, passed : Self::Passed, world : Arc<World>) -> Return;
 
+    /// Returns `true` if this system requests exclusive access to the entire [`World`].
+    ///
+    /// Scheduled systems with exclusive access are run while no other systems hold any world locks. See [`WorldMut`](crate::world::WorldMut).
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    /// Returns `false` if this system never holds locks on values in the [`World`].
+    ///
+    /// Scheduled systems which hold no locks do not delay, and are not delayed by, systems with exclusive access.
+    fn holds_world_locks(&self) -> bool {
+        true
+    }
+
 }
 
 
@@ -101,6 +101,14 @@ where   A : System<(), Passed = ()>,
 
     #[track_caller]
     async unsafe fn acquire_and_run(&mut self, a_passed : Self::Passed, world : Arc<World>) -> () {
+        if (self.is_exclusive()) {
+            // A system with exclusive access can not run alongside the other.
+            // SAFETY: TODO
+            unsafe{ self.a.acquire_and_run(a_passed, Arc::clone(&world)) }.await;
+            // SAFETY: TODO
+            unsafe{ self.b.acquire_and_run((), world) }.await;
+            return;
+        }
         // SAFETY: TODO
         let a = unsafe{ self.a.acquire_and_run(a_passed, Arc::clone(&world)) };
         // SAFETY: TODO
@@ -108,6 +116,14 @@ where   A : System<(), Passed = ()>,
 
         join!(a, b).await;
     }
+
+    fn is_exclusive(&self) -> bool {
+        self.a.is_exclusive() || self.b.is_exclusive()
+    }
+
+    fn holds_world_locks(&self) -> bool {
+        self.a.holds_world_locks() || self.b.holds_world_locks()
+    }
 }
 
 unsafe impl<A, B>
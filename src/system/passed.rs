@@ -102,6 +102,14 @@ where   S      : System<Return, Passed = In<Passed>>,
         unsafe{ self.system.acquire_and_run(In(self.passed.clone()), world) }.await
     }
 
+    fn is_exclusive(&self) -> bool {
+        self.system.is_exclusive()
+    }
+
+    fn holds_world_locks(&self) -> bool {
+        self.system.holds_world_locks()
+    }
+
 }
 
 
@@ -113,6 +113,14 @@ where   A : System<(), Passed = APassed>,
         // SAFETY: TODO
         unsafe{ self.b.acquire_and_run((), world) }.await
     }
+
+    fn is_exclusive(&self) -> bool {
+        self.a.is_exclusive() || self.b.is_exclusive()
+    }
+
+    fn holds_world_locks(&self) -> bool {
+        self.a.holds_world_locks() || self.b.holds_world_locks()
+    }
 }
 
 unsafe impl<APassed, A, B, Return>
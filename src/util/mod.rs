@@ -9,6 +9,10 @@ pub(crate) mod either;
 
 pub(crate) mod sparsevec;
 
+pub(crate) mod wakers;
+
+pub(crate) mod stack;
+
 
 pub(crate) mod future;
 
@@ -8,6 +8,7 @@ use core::pin::Pin;
 use core::task::{ Context, Poll };
 use core::mem::ManuallyDrop;
 use alloc::sync::Arc;
+use crate::util::wakers::WakerList;
 
 
 /// TODO: Doc comments.
@@ -38,7 +39,10 @@ mod __internal {
         pub(super) state          : AtomicU32,
 
         /// The number of write-locks that are waiting.
-        pub(super) waiting_writes : AtomicU32
+        pub(super) waiting_writes : AtomicU32,
+
+        /// The wakers of futures waiting for the lock state to change.
+        pub(super) wakers         : WakerList
 
     }
 
@@ -55,7 +59,8 @@ impl<T> RwLock<T> {
         inner : Arc::new(RwLockInner {
             value          : UnsafeCell::new(value),
             state          : AtomicU32::new(0),
-            waiting_writes : AtomicU32::new(0)
+            waiting_writes : AtomicU32::new(0),
+            wakers         : WakerList::new()
         })
     } }
 
@@ -67,7 +72,8 @@ impl<T> RwLock<T> {
         inner : Arc::new(RwLockInner {
             value          : UnsafeCell::new(value),
             state          : AtomicU32::new(u32::MAX),
-            waiting_writes : AtomicU32::new(0)
+            waiting_writes : AtomicU32::new(0),
+            wakers         : WakerList::new()
         })
     } }
 
@@ -98,11 +104,10 @@ impl<T> RwLock<T> {
     ///
     /// The [`RwLock`] will be permanently locked if this succeeds.
     pub fn try_into_inner(self) -> Poll<T> {
-        self.inner.state.compare_exchange(0, u32::MAX, Ordering::Acquire, Ordering::Relaxed).is_ok()
-            // SAFETY: Lock state was 0 (no locks), and is now `u32::MAX` (write-locked).
-            //         The lock is never cleared, preventing access to the inner data again.
-            .then(|| unsafe{ Arc::into_inner(self.inner).unwrap_unchecked() }.value.into_inner() )
-            .map_or(Poll::Pending, |out| Poll::Ready(out))
+        match (take_inner(self.inner)) {
+            Ok(value) => Poll::Ready(value),
+            Err(_)    => Poll::Pending
+        }
     }
 
 }
@@ -141,6 +146,14 @@ impl<T> RwLockInner<T> {
         PendingRwLockWrite { lock : self.clone() }
     }
 
+    /// Calls `f`, registering the waker of `ctx` to be woken once the lock state changes if it returns [`Poll::Pending`].
+    fn poll_or_register<G>(&self, ctx : &mut Context<'_>, f : impl Fn() -> Poll<G>) -> Poll<G> {
+        if let Poll::Ready(out) = f() { return Poll::Ready(out); }
+        self.wakers.register(ctx.waker());
+        // Check again, in case the lock state changed before the waker was registered.
+        f()
+    }
+
     /// Returns a write-lock to this [`RwLock`], without checking state or locking.
     ///
     /// # Safety
@@ -176,8 +189,8 @@ impl<T> PendingRwLockRead<T> {
 impl<T> Future for PendingRwLockRead<T> {
     type Output = RwLockReadGuard<T>;
 
-    fn poll(self : Pin<&mut Self>, _ctx : &mut Context<'_>) -> Poll<Self::Output> {
-        self.lock.try_read()
+    fn poll(self : Pin<&mut Self>, ctx : &mut Context<'_>) -> Poll<Self::Output> {
+        self.lock.poll_or_register(ctx, || self.lock.try_read())
     }
 }
 
@@ -225,6 +238,7 @@ impl<T> Deref for RwLockReadGuard<T> {
 impl<T> Drop for RwLockReadGuard<T> {
     fn drop(&mut self) {
         let _ = self.lock.state.fetch_sub(1, Ordering::Release);
+        self.lock.wakers.wake_all();
         // SAFETY: TODO
         unsafe{ ManuallyDrop::drop(&mut self.lock); }
     }
@@ -254,14 +268,16 @@ impl<T> PendingRwLockWrite<T> {
 impl<T> Future for PendingRwLockWrite<T> {
     type Output = RwLockWriteGuard<T>;
 
-    fn poll(self : Pin<&mut Self>, _ctx : &mut Context<'_>) -> Poll<Self::Output> {
-        self.lock.try_write()
+    fn poll(self : Pin<&mut Self>, ctx : &mut Context<'_>) -> Poll<Self::Output> {
+        self.lock.poll_or_register(ctx, || self.lock.try_write())
     }
 }
 
 impl<T> Drop for PendingRwLockWrite<T> {
     fn drop(&mut self) {
+        // Readers wait while writes are waiting.
         let _ = self.lock.waiting_writes.fetch_sub(1, Ordering::Relaxed);
+        self.lock.wakers.wake_all();
     }
 }
 
@@ -291,14 +307,16 @@ impl<T> PendingRwLockUpgrade<T> {
 impl<T> Future for PendingRwLockUpgrade<T> {
     type Output = RwLockWriteGuard<T>;
 
-    fn poll(self : Pin<&mut Self>, _ctx : &mut Context<'_>) -> Poll<Self::Output> {
-        self.try_write()
+    fn poll(self : Pin<&mut Self>, ctx : &mut Context<'_>) -> Poll<Self::Output> {
+        self.lock.poll_or_register(ctx, || self.try_write())
     }
 }
 
 impl<T> Drop for PendingRwLockUpgrade<T> {
     fn drop(&mut self) {
+        // Readers wait while writes are waiting.
         let _ = self.lock.waiting_writes.fetch_sub(1, Ordering::Relaxed);
+        self.lock.wakers.wake_all();
     }
 }
 
@@ -320,6 +338,7 @@ impl<T> RwLockWriteGuard<T> {
     pub fn downgrade(guard : Self) -> RwLockReadGuard<T> {
         let mut guard = ManuallyDrop::new(guard);
         guard.lock.state.store(1, Ordering::Relaxed);
+        guard.lock.wakers.wake_all();
         // SAFETY: TODO
         RwLockReadGuard { lock : ManuallyDrop::new(unsafe{ ManuallyDrop::take(&mut guard.lock) }) }
     }
@@ -343,6 +362,7 @@ impl<T> DerefMut for RwLockWriteGuard<T> {
 impl<T> Drop for RwLockWriteGuard<T> {
     fn drop(&mut self) {
         let _ = self.lock.state.store(0, Ordering::Release);
+        self.lock.wakers.wake_all();
         // SAFETY: TODO
         unsafe{ ManuallyDrop::drop(&mut self.lock); }
     }
@@ -364,16 +384,44 @@ impl<T> Unpin for PendingRwLockOwn<T> { }
 
 impl<T> PendingRwLockOwn<T> {
 
-    /// TODO: Doc comments
+    /// Tries to take ownership of the inner data.
+    ///
+    /// This succeeds once the [`RwLock`] is not locked, and no other handles to it exist.
+    ///
+    /// # Panics
+    /// Panics if this has already succeeded.
     pub fn try_into_inner(&mut self) -> Poll<T> {
-        self.lock.as_ref().unwrap().state.compare_exchange(1, u32::MAX, Ordering::Acquire, Ordering::Relaxed).is_ok()
-            // SAFETY: TODO
-            .then(|| unsafe{ Arc::into_inner(self.lock.take().unwrap_unchecked()).unwrap_unchecked() }.value.into_inner() )
-            .map_or(Poll::Pending, |out| Poll::Ready(out))
+        let lock = self.lock.take().expect("RwLock inner data has already been taken");
+        match (take_inner(lock)) {
+            Ok(value) => Poll::Ready(value),
+            Err(lock) => {
+                self.lock = Some(lock);
+                Poll::Pending
+            }
+        }
     }
 
 }
 
+
+/// Takes ownership of the inner data of a [`RwLock`], if it is not locked and `lock` is the only handle to it.
+///
+/// Otherwise, `lock` is returned unchanged.
+fn take_inner<T>(lock : Arc<RwLockInner<T>>) -> Result<T, Arc<RwLockInner<T>>> {
+    if (lock.state.compare_exchange(0, u32::MAX, Ordering::Acquire, Ordering::Relaxed).is_err()) {
+        return Err(lock);
+    }
+    // Guards release the lock state before dropping their handle, and pending futures hold handles too,
+    //  so the lock being unlocked does not mean that this is the only handle.
+    match (Arc::try_unwrap(lock)) {
+        Ok(inner) => Ok(inner.value.into_inner()),
+        Err(lock) => {
+            lock.state.store(0, Ordering::Release);
+            Err(lock)
+        }
+    }
+}
+
 impl<T> Future for PendingRwLockOwn<T> {
     type Output = T;
 
@@ -381,3 +429,78 @@ impl<T> Future for PendingRwLockOwn<T> {
         self.try_into_inner()
     }
 }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::task::Waker;
+    use alloc::boxed::Box;
+    use alloc::task::Wake;
+
+    #[test]
+    fn into_inner_waits_for_other_handles() {
+        let lock        = RwLock::new(5);
+        let other       = RwLock::arc_clone(&lock);
+        let mut pending = lock.into_inner();
+        assert!(pending.try_into_inner().is_pending());
+        // The lock is usable again after a failed attempt.
+        assert!(other.try_write().is_ready());
+        drop(other);
+        assert_eq!(pending.try_into_inner(), Poll::Ready(5));
+    }
+
+    #[test]
+    fn into_inner_waits_for_guards() {
+        let lock = RwLock::new(5);
+        let Poll::Ready(guard) = lock.try_read() else { panic!("lock is not readable") };
+        let mut pending = lock.into_inner();
+        assert!(pending.try_into_inner().is_pending());
+        drop(guard);
+        assert_eq!(pending.try_into_inner(), Poll::Ready(5));
+    }
+
+    #[test]
+    fn try_into_inner_fails_while_shared() {
+        let lock  = RwLock::new(5);
+        let other = RwLock::arc_clone(&lock);
+        assert!(lock.try_into_inner().is_pending());
+        assert!(other.try_read().is_ready());
+        assert_eq!(other.try_into_inner(), Poll::Ready(5));
+    }
+
+    /// A waker which counts the number of times it has been woken.
+    struct CountingWaker(AtomicU32);
+    impl Wake for CountingWaker {
+        fn wake(self : Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn waiting_is_woken_once_unlocked() {
+        let lock    = RwLock::new(5);
+        let counter = Arc::new(CountingWaker(AtomicU32::new(0)));
+        let waker   = Waker::from(Arc::clone(&counter));
+        let mut ctx = Context::from_waker(&waker);
+
+        // Readers are woken once the write-lock is released.
+        let Poll::Ready(write) = lock.try_write() else { panic!("lock is not writable") };
+        let mut read = Box::pin(lock.read());
+        assert!(read.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+        drop(write);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        let Poll::Ready(read) = read.as_mut().poll(&mut ctx) else { panic!("lock is not readable") };
+
+        // Writers are woken once the last read-lock is released.
+        let mut write = Box::pin(lock.write());
+        assert!(write.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        drop(read);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+        assert!(write.as_mut().poll(&mut ctx).is_ready());
+    }
+
+}
@@ -0,0 +1,102 @@
+//! A lock-free stack which values can be pushed to from anywhere, including [`Drop`] implementations.
+
+
+use core::sync::atomic::{ AtomicPtr, Ordering };
+use core::ptr;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+
+/// A lock-free stack. Values are pushed one at a time, and taken all at once.
+///
+/// Pushing never waits on another thread, so it is safe to do from places which can not await, such as [`Drop`] implementations.
+pub(crate) struct AtomicStack<T> {
+
+    /// The most recently pushed node, or null if the stack is empty.
+    head : AtomicPtr<AtomicStackNode<T>>
+
+}
+
+/// A single value in an [`AtomicStack`].
+struct AtomicStackNode<T> {
+
+    /// The value.
+    value : T,
+
+    /// The node pushed before this one, or null if this is the first.
+    next  : *mut AtomicStackNode<T>
+
+}
+
+// SAFETY: Nodes are only ever accessed by the thread which pushed them, or the thread which took them.
+unsafe impl<T : Send> Sync for AtomicStack<T> { }
+unsafe impl<T : Send> Send for AtomicStack<T> { }
+
+impl<T> AtomicStack<T> {
+
+    /// Creates an empty [`AtomicStack`].
+    pub(crate) fn new() -> Self { Self {
+        head : AtomicPtr::new(ptr::null_mut())
+    } }
+
+    /// Returns `true` if nothing has been pushed since the stack was last taken.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Pushes a value onto the stack.
+    pub(crate) fn push(&self, value : T) {
+        let     node = Box::into_raw(Box::new(AtomicStackNode { value, next : ptr::null_mut() }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: `node` is not visible to any other thread until the exchange below succeeds.
+            unsafe{ (*node).next = head; }
+            match (self.head.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)) {
+                Ok(_)       => { break; },
+                Err(actual) => { head = actual; }
+            }
+        }
+    }
+
+    /// Removes every value from the stack, returning them in the order they were pushed.
+    pub(crate) fn take(&self) -> Vec<T> {
+        let mut node   = self.head.swap(ptr::null_mut(), Ordering::Acquire);
+        let mut values = Vec::new();
+        while (! node.is_null()) {
+            // SAFETY: The swap above made this thread the only owner of every node in the taken list.
+            let AtomicStackNode { value, next } = *unsafe{ Box::from_raw(node) };
+            values.push(value);
+            node = next;
+        }
+        values.reverse();
+        values
+    }
+
+}
+
+impl<T> Drop for AtomicStack<T> {
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_returns_values_in_push_order() {
+        let stack = AtomicStack::new();
+        assert!(stack.is_empty());
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert!(! stack.is_empty());
+        assert_eq!(stack.take(), [0, 1, 2, 3, 4]);
+        assert!(stack.is_empty());
+        assert!(stack.take().is_empty());
+    }
+
+}
@@ -0,0 +1,63 @@
+//! A list of [`Waker`]s for futures waiting on a shared value.
+
+
+use core::task::Waker;
+use core::sync::atomic::{ AtomicBool, Ordering };
+use core::cell::UnsafeCell;
+use core::hint::spin_loop;
+use core::mem;
+use alloc::vec::Vec;
+
+
+/// A list of [`Waker`]s, guarded by a spin lock which is only ever held while adding or taking wakers.
+pub(crate) struct WakerList {
+
+    /// Whether `wakers` is being accessed.
+    locked : AtomicBool,
+
+    /// The wakers.
+    wakers : UnsafeCell<Vec<Waker>>
+
+}
+
+// SAFETY: `wakers` is only accessed while `locked` is held.
+unsafe impl Sync for WakerList { }
+unsafe impl Send for WakerList { }
+
+impl WakerList {
+
+    /// Creates an empty [`WakerList`].
+    pub(crate) fn new() -> Self { Self {
+        locked : AtomicBool::new(false),
+        wakers : UnsafeCell::new(Vec::new())
+    } }
+
+    /// Runs `f` with the wakers locked.
+    fn with<T>(&self, f : impl FnOnce(&mut Vec<Waker>) -> T) -> T {
+        while (self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err()) {
+            spin_loop();
+        }
+        // SAFETY: `locked` is held.
+        let wakers = unsafe{ &mut *self.wakers.get() };
+        let out    = f(wakers);
+        self.locked.store(false, Ordering::Release);
+        out
+    }
+
+    /// Adds a waker to be woken by the next call to [`WakerList::wake_all`], if an equivalent one isn't already waiting.
+    pub(crate) fn register(&self, waker : &Waker) {
+        self.with(|wakers| {
+            if (! wakers.iter().any(|other| other.will_wake(waker))) {
+                wakers.push(waker.clone());
+            }
+        });
+    }
+
+    /// Wakes and removes every waiting waker.
+    pub(crate) fn wake_all(&self) {
+        for waker in self.with(mem::take) {
+            waker.wake();
+        }
+    }
+
+}
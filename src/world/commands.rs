@@ -2,11 +2,15 @@
 
 
 use crate::app::AppExit;
-use crate::world::World;
+use crate::world::{ World, FromWorld };
 use crate::resource::Resource;
 use crate::entity::Entity;
 use crate::component::bundle::ComponentBundle;
 use crate::query::{ Query, QueryAcquireResult, QueryValidator };
+#[cfg(not(feature = "no_std"))]
+use crate::query::Event;
+#[cfg(not(feature = "no_std"))]
+use crate::world::Trigger;
 use crate::system::{ SystemId, IntoSystem, System };
 use core::task::Poll;
 use alloc::boxed::Box;
@@ -29,6 +33,11 @@ unsafe impl Sync for Commands { }
 
 impl Commands {
 
+    /// Creates a new [`Commands`] operating on the given [`World`].
+    pub(crate) fn new(world : Arc<World>) -> Self { Self {
+        world
+    } }
+
     /*/// TODO: Doc comments
     ///
     /// # Warning
@@ -62,6 +71,15 @@ impl Commands {
         ))
     }
 
+    /// Inserts a [`Resource`], constructing it with [`FromWorld`], if one of the same type does not already exist.
+    ///
+    /// See [`World::init_resource`].
+    pub async fn init_resource<R : Resource + FromWorld + 'static>(&self) {
+        self.world.cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.init_resource::<R>().await })
+        ))
+    }
+
     /// TODO: Doc comments
     pub async fn remove_resource<R : Resource + 'static>(&self) {
         self.world.cmd_queue.write().await.push(Box::new(move |world|
@@ -69,6 +87,24 @@ impl Commands {
         ))
     }
 
+    /// Inserts a [`Resource`] keyed by `K`.
+    ///
+    /// See [`World::insert_resource_keyed`].
+    pub async fn insert_resource_keyed<R : Resource + 'static, K : 'static>(&self, resource : R) {
+        self.world.cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.insert_resource_keyed::<R, K>(resource).await })
+        ))
+    }
+
+    /// Removes a [`Resource`] keyed by `K`.
+    ///
+    /// See [`World::remove_resource_keyed`].
+    pub async fn remove_resource_keyed<R : Resource + 'static, K : 'static>(&self) {
+        self.world.cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.remove_resource_keyed::<R, K>().await })
+        ))
+    }
+
     /// TODO: Doc comments
     pub async fn spawn<B : ComponentBundle + 'static>(&self, bundle : B) { // TODO: Immediately reserve space for the entities.
         self.world.cmd_queue.write().await.push(Box::new(move |world|
@@ -111,6 +147,28 @@ impl Commands {
         })));
     }
 
+    /// Attaches an observer system to an entity.
+    ///
+    /// See [`World::observe`].
+    #[cfg(not(feature = "no_std"))]
+    pub async fn observe<E : Event + 'static, S : IntoSystem<Params, ()> + 'static, Params>(&self, entity : Entity, system : S)
+    where S::System : System<(), Passed = Trigger<E>> + Send + Sync + 'static
+    {
+        self.world.cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.observe(entity, system).await })
+        ))
+    }
+
+    /// Triggers an event on an entity, running its observer systems once the current systems have finished.
+    ///
+    /// See [`World::trigger`].
+    #[cfg(not(feature = "no_std"))]
+    pub async fn trigger<E : Event + Send + Sync + 'static>(&self, entity : Entity, event : E) {
+        self.world.deferred_cmd_queue.write().await.push(Box::new(move |world|
+            Box::pin(async move { world.trigger(entity, event).await })
+        ))
+    }
+
 }
 
 
@@ -125,7 +183,7 @@ unsafe impl Query for Commands {
     }
 
     unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
-        Poll::Ready(QueryAcquireResult::Ready(Commands{ world }))
+        Poll::Ready(QueryAcquireResult::Ready(Commands::new(world)))
     }
 
     fn validate() -> QueryValidator {
@@ -0,0 +1,228 @@
+//! Systems with exclusive access to the entire world.
+
+
+use crate::world::World;
+use crate::system::SystemId;
+use crate::query::{ Query, ReadOnlyQuery, QueryAcquireResult, QueryValidator };
+use core::ops::Deref;
+use core::task::Poll;
+use alloc::sync::Arc;
+
+
+/// Exclusive access to the entire [`World`], as a system parameter.
+///
+/// When a scheduled system requests this, the scheduler waits until no other systems hold any world locks, runs the system alone,
+///  and then resumes the others. Systems which request no world values, like those taking only [`Commands`](crate::world::Commands),
+///  keep running alongside it. This is useful for maintenance work which touches large parts of the world at once,
+///  such as moving many entities between archetypes, serialising the world, or running other schedules.
+///
+/// No other world values can be requested by the same system, as they could not be accessed through the world while they are held.
+/// Parameters which do not hold world values, like [`Commands`](crate::world::Commands) and [`Local`](crate::query::Local), are allowed.
+/// See [`WorldRef`] for read-only access.
+///
+/// While a system with exclusive access is waiting, systems which would hold world locks are not started, so that it is not starved.
+/// This means that one long-running system holds back every other system which holds world locks until it finishes and the exclusive system has run.
+///
+/// Systems which are not run by a scheduler, like those run using [`World::system_mut`], are not made to wait for other systems.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Enemy;
+///
+/// async fn despawn_all_enemies(
+///     world : WorldMut
+/// ) {
+///     let enemies = world.query::<Entities<Entity, With<Enemy>>>().acquire().await.iter().collect::<Vec<_>>();
+///     for enemy in enemies {
+///         world.despawn(enemy).await;
+///     }
+/// }
+/// ```
+pub struct WorldMut {
+
+    /// The world.
+    world : Arc<World>
+
+}
+
+impl Deref for WorldMut {
+    type Target = Arc<World>;
+    fn deref(&self) -> &Self::Target {
+        &self.world
+    }
+}
+
+unsafe impl Query for WorldMut {
+
+    type Item = WorldMut;
+
+    type State = ();
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+
+    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        Poll::Ready(QueryAcquireResult::Ready(WorldMut { world }))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::of_exclusive()
+    }
+
+}
+
+
+/// Read-only exclusive access to the entire [`World`], as a system parameter. See [`WorldMut`].
+///
+/// Unlike [`WorldMut`], this can be requested by read-only systems, like those run using [`World::system`].
+pub struct WorldRef {
+
+    /// The world.
+    world : Arc<World>
+
+}
+
+impl Deref for WorldRef {
+    type Target = Arc<World>;
+    fn deref(&self) -> &Self::Target {
+        &self.world
+    }
+}
+
+unsafe impl Query for WorldRef {
+
+    type Item = WorldRef;
+
+    type State = ();
+
+    fn init_state(_world : Arc<World>, _system_id : Option<SystemId>) -> Self::State { }
+
+    unsafe fn acquire(world : Arc<World>, _state : &mut Self::State) -> Poll<QueryAcquireResult<Self::Item>> {
+        Poll::Ready(QueryAcquireResult::Ready(WorldRef { world }))
+    }
+
+    fn validate() -> QueryValidator {
+        QueryValidator::of_exclusive()
+    }
+
+}
+
+unsafe impl ReadOnlyQuery for WorldRef { }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Commands;
+    use crate::resource::{ Resource, Res };
+
+    struct Counter;
+    impl Resource for Counter { }
+
+    #[test]
+    fn world_queries_are_exclusive() {
+        <(WorldMut, WorldRef, Commands)>::validate().panic_on_violation();
+        assert!(<(WorldMut, Commands)>::validate().is_exclusive());
+        assert!(<(WorldRef,)>::validate().is_exclusive());
+        assert!(! <(Commands,)>::validate().is_exclusive());
+    }
+
+    #[test]
+    #[should_panic]
+    fn exclusive_access_can_not_be_shared() {
+        <(WorldMut, Res<&Counter>)>::validate().panic_on_violation();
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn exclusive_systems_run_alone() { async_std::task::block_on(async {
+        use crate::app::{ App, AppExit };
+        use crate::app::plugin::CycleSchedulerPlugin;
+        use crate::schedule::label::Cycle;
+        use crate::schedule::system::IntoScheduledSystemConfig;
+        use crate::query::Local;
+        use async_std::task::yield_now;
+        use core::sync::atomic::{ AtomicUsize, Ordering };
+
+        static SHARED    : AtomicUsize = AtomicUsize::new(0);
+        static EXCLUSIVE : AtomicUsize = AtomicUsize::new(0);
+        static RAN       : AtomicUsize = AtomicUsize::new(0);
+
+        async fn shared(_counter : Res<&Counter>) {
+            SHARED.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..5 {
+                assert_eq!(EXCLUSIVE.load(Ordering::SeqCst), 0);
+                yield_now().await;
+            }
+            SHARED.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        async fn exclusive() {
+            EXCLUSIVE.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..5 {
+                assert_eq!(SHARED.load(Ordering::SeqCst), 0);
+                yield_now().await;
+            }
+            EXCLUSIVE.fetch_sub(1, Ordering::SeqCst);
+            RAN.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut app = App::new();
+        app.add_plugin(CycleSchedulerPlugin);
+        app.insert_resource(Counter);
+        // Systems which hold no world locks do not hold back exclusive systems.
+        app.add_systems(Cycle, async |_cmds : Commands| {
+            while (RAN.load(Ordering::SeqCst) == 0) { yield_now().await; }
+        });
+        app.add_systems(Cycle, shared);
+        app.add_systems(Cycle, async |_counter : Res<&Counter>| shared(_counter).await);
+        app.add_systems(Cycle, async |_world : WorldMut| exclusive().await);
+        // Conditions with exclusive access make the whole system exclusive.
+        app.add_systems(Cycle, (async || { }).run_if(async |_world : WorldRef| { exclusive().await; true }));
+        app.add_systems(Cycle, async |cmds : Commands, mut cycles : Local<usize>| {
+            *cycles += 1;
+            if (*cycles >= 10) { cmds.try_exit(AppExit::Ok); }
+        });
+        assert!(matches!(app.run().await, AppExit::Ok));
+    }); }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn waiting_exclusive_systems_hold_back_others() { async_std::task::block_on(async {
+        use crate::app::{ App, AppExit };
+        use crate::app::plugin::CycleSchedulerPlugin;
+        use crate::schedule::label::Cycle;
+        use crate::query::Local;
+        use async_std::task::yield_now;
+        use std::sync::Mutex;
+
+        static LOG : Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+        let mut app = App::new();
+        app.add_plugin(CycleSchedulerPlugin);
+        app.insert_resource(Counter);
+        // Systems are started in the order they were added, so the exclusive system starts waiting while `long` runs.
+        app.add_systems(Cycle, async |_counter : Res<&Counter>, mut ran : Local<bool>| {
+            if (*ran) { return; }
+            *ran = true;
+            LOG.lock().unwrap().push("long start");
+            for _ in 0..5 { yield_now().await; }
+            LOG.lock().unwrap().push("long end");
+        });
+        app.add_systems(Cycle, async |_world : WorldMut, mut ran : Local<bool>| {
+            if (! *ran) { *ran = true; LOG.lock().unwrap().push("exclusive"); }
+        });
+        app.add_systems(Cycle, async |_counter : Res<&Counter>, mut ran : Local<bool>| {
+            if (! *ran) { *ran = true; LOG.lock().unwrap().push("other"); }
+        });
+        app.add_systems(Cycle, async |cmds : Commands| {
+            if (LOG.lock().unwrap().len() >= 4) { cmds.try_exit(AppExit::Ok); }
+        });
+        assert!(matches!(app.run().await, AppExit::Ok));
+        assert_eq!(*LOG.lock().unwrap(), ["long start", "long end", "exclusive", "other"]);
+    }); }
+
+}
@@ -0,0 +1,48 @@
+//! Constructing values from a [`World`].
+
+
+use crate::world::World;
+use alloc::sync::Arc;
+
+
+/// A type which can be constructed using the contents of a [`World`].
+///
+/// This is implemented for every type which implements [`Default`].
+/// See [`App::init_resource`](crate::app::App::init_resource) and [`World::init_resource`].
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use axecs::world::FromWorld;
+/// use std::sync::Arc;
+///
+/// #[derive(Resource)]
+/// struct WindowSize {
+///     width  : u32,
+///     height : u32
+/// }
+///
+/// #[derive(Resource)]
+/// struct Viewport {
+///     aspect : f32
+/// }
+///
+/// impl FromWorld for Viewport {
+///     async fn from_world(world : &Arc<World>) -> Self {
+///         let size = world.get_resource_ref::<WindowSize>().await.unwrap();
+///         Self { aspect : (size.width as f32) / (size.height as f32) }
+///     }
+/// }
+/// ```
+pub trait FromWorld : Sized {
+
+    /// Constructs a value using the contents of the given [`World`].
+    async fn from_world(world : &Arc<World>) -> Self;
+
+}
+
+impl<T : Default> FromWorld for T {
+    async fn from_world(_world : &Arc<World>) -> Self {
+        T::default()
+    }
+}
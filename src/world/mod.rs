@@ -4,19 +4,36 @@
 mod commands;
 pub use commands::*;
 
+mod from_world;
+pub use from_world::*;
 
-use crate::resource::{ Resource, ResourceStorage, ResourceCellReadGuard, ResourceCellWriteGuard };
+mod exclusive;
+pub use exclusive::*;
+
+#[cfg(not(feature = "no_std"))]
+mod observer;
+#[cfg(not(feature = "no_std"))]
+pub use observer::Trigger;
+#[cfg(not(feature = "no_std"))]
+use observer::ObserverStorage;
+
+
+use crate::resource::{ Resource, ResourceStorage, ResourceCellReadGuard, ResourceCellWriteGuard, NonSendStorage };
 use crate::entity::Entity;
+use crate::component::ComponentTypeInfo;
 use crate::component::bundle::ComponentBundle;
 use crate::component::archetype::ArchetypeStorage;
-use crate::query::{ Query, ReadOnlyQuery, PersistentQueryState };
+use crate::query::{ Query, ReadOnlyQuery, PersistentQueryState, Event, EventSender, EventReceiver };
 use crate::system::{ SystemId, IntoSystem, IntoReadOnlySystem, ReadOnlySystem, PersistentSystemState };
+#[cfg(not(feature = "no_std"))]
+use crate::system::System;
 use crate::app::AppExit;
 use crate::schedule::system::TypeErasedSystem;
-use core::any::TypeId;
+use core::any::{ TypeId, type_name };
+use core::ops::AsyncFnOnce;
 use core::cell::SyncUnsafeCell;
 use core::mem::MaybeUninit;
-use core::sync::atomic::{ AtomicU8, Ordering };
+use core::sync::atomic::{ AtomicU8, AtomicU64, Ordering };
 use core::pin::Pin;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
@@ -40,9 +57,15 @@ pub struct World {
     /// The [`AppExit`] status of the app.
     exit_status : SyncUnsafeCell<MaybeUninit<AppExit>>,
 
+    /// The number of cycles that have been completed in this world, shared with its [`ResourceStorage`].
+    cycle       : Arc<AtomicU64>,
+
     /// The [`Resource`]s in this world.
     resources   : ResourceStorage,
 
+    /// The non-[`Send`] resources in this world.
+    non_send    : NonSendStorage,
+
     /// The [`Component`](crate::component::Component) [`Archetype`](crate::component::archetype::Archetype)s in this world.
     archetypes  : ArchetypeStorage,
 
@@ -53,7 +76,14 @@ pub struct World {
     pub(crate) deferred_cmd_queue : RwLock<Vec<Box<dyn (FnOnce(Arc<World>) -> Pin<Box<dyn Future<Output = ()>>>) + Send + Sync>>>,
 
     /// TODO: Doc comments
-    pub(crate) ran_systems : RwLock<BTreeSet<TypeId>>
+    pub(crate) ran_systems : RwLock<BTreeSet<TypeId>>,
+
+    /// Held for reading by each scheduled system while it runs, and for writing by scheduled systems with exclusive access. See [`WorldMut`].
+    pub(crate) exclusive : RwLock<()>,
+
+    /// The observer systems attached to entities in this world.
+    #[cfg(not(feature = "no_std"))]
+    observers : RwLock<ObserverStorage>
 
 }
 
@@ -65,6 +95,12 @@ impl World {
         &self.resources
     }
 
+    /// Returns a reference to the non-[`Send`] resources in this world.
+    #[inline]
+    pub fn non_send_resources(&self) -> &NonSendStorage {
+        &self.non_send
+    }
+
     /// Returns a reference to the [`Archetype`](crate::component::archetype::Archetype)s in this world.
     #[inline]
     pub fn archetypes(&self) -> &ArchetypeStorage {
@@ -83,14 +119,27 @@ impl World {
 
     /// creates a new [`World`] with some [`Resource`]s in it to start.
     #[inline]
-    pub fn new_with(resources : ResourceStorage) -> Self { Self {
+    pub fn new_with(resources : ResourceStorage) -> Self {
+        Self::new_with_non_send(resources, NonSendStorage::new())
+    }
+
+    /// Creates a new [`World`] with some [`Resource`]s and non-[`Send`] resources in it to start.
+    ///
+    /// The world will be owned by the thread that `non_send` was created on. See [`NonSendStorage`].
+    #[inline]
+    pub fn new_with_non_send(resources : ResourceStorage, non_send : NonSendStorage) -> Self { Self {
         is_exiting         : AtomicU8::new(0),
         exit_status        : SyncUnsafeCell::new(MaybeUninit::uninit()),
+        cycle              : resources.cycle_handle(),
         resources,
+        non_send,
         archetypes         : ArchetypeStorage::new(),
         cmd_queue          : RwLock::new(Vec::new()),
         deferred_cmd_queue : RwLock::new(Vec::new()),
-        ran_systems        : RwLock::new(BTreeSet::new())
+        ran_systems        : RwLock::new(BTreeSet::new()),
+        exclusive          : RwLock::new(()),
+        #[cfg(not(feature = "no_std"))]
+        observers          : RwLock::new(ObserverStorage::new())
     } }
 
 
@@ -148,6 +197,22 @@ impl World {
     }
 
 
+    /// Returns the number of cycles that have been completed in this world.
+    ///
+    /// The [`CycleSchedulerPlugin`](crate::app::plugin::CycleSchedulerPlugin) completes a cycle once every
+    ///  [`Cycle`](crate::schedule::label::Cycle) system has finished running at least once since the previous cycle.
+    pub fn cycle(&self) -> u64 {
+        self.cycle.load(Ordering::Relaxed)
+    }
+
+    /// Marks the current cycle as completed, returning the new cycle count.
+    ///
+    /// This is intended for runner functions and likely should not be used otherwise. See [`App::set_runner`](crate::app::App::set_runner).
+    pub fn advance_cycle(&self) -> u64 {
+        self.cycle.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+
     /// Inserts a [`Resource`] into this world, overwriting any previous resource of the same type.
     ///
     /// This is more efficient than [`World::replace_resource`], as it doesn't have to wait for the individual resource to lock.
@@ -162,6 +227,38 @@ impl World {
         self.resources.replace::<R>(resource).await
     }
 
+    /// Inserts a [`Resource`] keyed by `K` into this world, overwriting any previous resource of the same type and key.
+    ///
+    /// Keyed [`Resource`]s are accessed using [`Res<&R, K>`](crate::resource::Res).
+    /// [`World::insert_resource`] is the same as using `()` as the key.
+    pub async fn insert_resource_keyed<R : Resource + 'static, K : 'static>(self : &Arc<Self>, resource : R) {
+        self.resources.insert_keyed::<R, K>(resource).await
+    }
+
+    /// Inserts a [`Resource`] keyed by `K` into this world, returning the old resource of the same type and key if it existed.
+    pub async fn replace_resource_keyed<R : Resource + 'static, K : 'static>(self : &Arc<Self>, resource : R) -> Option<R> {
+        self.resources.replace_keyed::<R, K>(resource).await
+    }
+
+    /// Inserts a [`Resource`] into this world, constructing it with [`FromWorld`], if one of the same type does not already exist.
+    ///
+    /// No locks are held while the [`Resource`] is constructed. Only un-keyed [`Resource`]s can be initialised.
+    pub async fn init_resource<R : Resource + FromWorld + 'static>(self : &Arc<Self>) {
+        if (! self.resources.contains::<R>().await) {
+            let resource = R::from_world(self).await;
+            self.resources.try_insert::<R>(resource).await;
+        }
+    }
+
+    /// Runs the initialisers registered with [`RawResourceStorage::init`](crate::resource::RawResourceStorage::init) which have not been run yet, in the order they were registered.
+    ///
+    /// App runners should call this once, after the world has been created.
+    pub async fn run_resource_initialisers(self : &Arc<Self>) {
+        for initialiser in self.resources.take_initialisers().await {
+            initialiser(Arc::clone(self)).await;
+        }
+    }
+
     /// Removes a [`Resource`] from this world.
     ///
     /// This is more efficient than [`World::take_resource`], as it doesn't have to wait for the individual resource to lock.
@@ -176,6 +273,91 @@ impl World {
         self.resources.take::<R>().await
     }
 
+    /// Removes a [`Resource`] keyed by `K` from this world.
+    pub async fn remove_resource_keyed<R : Resource + 'static, K : 'static>(self : &Arc<Self>) {
+        self.resources.remove_keyed::<R, K>().await
+    }
+
+    /// Removes a [`Resource`] keyed by `K` from this world, returning it if it existed.
+    pub async fn take_resource_keyed<R : Resource + 'static, K : 'static>(self : &Arc<Self>) -> Option<R> {
+        self.resources.take_keyed::<R, K>().await
+    }
+
+    /// Inserts a non-[`Send`] resource into this world, overwriting any previous value of the same type.
+    ///
+    /// See [`NonSend`](crate::resource::NonSend).
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this world.
+    #[track_caller]
+    pub async fn insert_non_send_resource<R : 'static>(self : &Arc<Self>, resource : R) {
+        self.non_send.insert::<R>(resource).await
+    }
+
+    /// Removes a non-[`Send`] resource from this world.
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this world.
+    #[track_caller]
+    pub async fn remove_non_send_resource<R : 'static>(self : &Arc<Self>) {
+        self.non_send.remove::<R>().await
+    }
+
+    /// Removes a non-[`Send`] resource from this world, returning it if it existed.
+    ///
+    /// # Panics
+    /// Panics if the current thread does not own this world.
+    #[track_caller]
+    pub async fn take_non_send_resource<R : 'static>(self : &Arc<Self>) -> Option<R> {
+        self.non_send.take::<R>().await
+    }
+
+    /// Temporarily removes a [`Resource`] from this world, giving mutable access to it alongside the world.
+    ///
+    /// The [`Resource`] is reinserted once `f` finishes, even if it panics or the returned future is dropped early.
+    /// If the storage is locked at that point, reinsertion is deferred to the world's command queue.
+    /// While `f` is running, the [`Resource`] does not exist in this world.
+    /// If a [`Resource`] of the same type is inserted while `f` is running, it is overwritten.
+    /// Only un-keyed [`Resource`]s can be scoped.
+    ///
+    /// ### Examples
+    /// ```rust
+    /// use axecs::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Resource)]
+    /// struct SpawnQueue {
+    ///     pending : Vec<usize>
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Id(usize);
+    ///
+    /// async fn flush_spawn_queue(world : &Arc<World>) {
+    ///     world.resource_scope(async |world, queue : &mut SpawnQueue| {
+    ///         world.spawn_batch(queue.pending.drain(..).map(|id| (Id(id),))).await.count();
+    ///     }).await;
+    /// }
+    /// ```
+    ///
+    /// ### Panics
+    /// Panics if the [`Resource`] does not exist.
+    /// See [`World::try_resource_scope`] for a non-panicking variant.
+    #[track_caller]
+    pub async fn resource_scope<R : Resource + 'static, T>(self : &Arc<Self>, f : impl AsyncFnOnce(&Arc<World>, &mut R) -> T) -> T {
+        match (self.resources.scope::<R, T>(self, f).await) {
+            Some(out) => out,
+            None      => { panic!("World does not have resource {}", type_name::<R>()) }
+        }
+    }
+
+    /// Temporarily removes a [`Resource`] from this world, giving mutable access to it alongside the world.
+    ///
+    /// See [`World::resource_scope`]. Returns `None` if the [`Resource`] does not exist.
+    pub async fn try_resource_scope<R : Resource + 'static, T>(self : &Arc<Self>, f : impl AsyncFnOnce(&Arc<World>, &mut R) -> T) -> Option<T> {
+        self.resources.scope::<R, T>(self, f).await
+    }
+
     /// Returns a reference to a [`Resource`] if it exists.
     pub async fn get_resource_ref<R : Resource + 'static>(&self) -> Option<ResourceCellReadGuard<'_, R>> {
         self.resources.get_ref::<R>().await
@@ -186,7 +368,17 @@ impl World {
         self.resources.get_mut::<R>().await
     }
 
-    /// Returns a mutable reference to a [`Resource`], creating it if needed.
+    /// Returns a reference to a [`Resource`] keyed by `K` if it exists.
+    pub async fn get_resource_ref_keyed<R : Resource + 'static, K : 'static>(&self) -> Option<ResourceCellReadGuard<'_, R>> {
+        self.resources.get_ref_keyed::<R, K>().await
+    }
+
+    /// Returns a mutable reference to a [`Resource`] keyed by `K` if it exists.
+    pub async fn get_resource_mut_keyed<R : Resource + 'static, K : 'static>(&self) -> Option<ResourceCellWriteGuard<'_, R>> {
+        self.resources.get_mut_keyed::<R, K>().await
+    }
+
+    /// Returns a mutable reference to an un-keyed [`Resource`], creating it if needed.
     pub async fn get_resource_mut_or_insert<R : Resource + 'static>(&self, f : impl FnOnce() -> R) -> ResourceCellWriteGuard<'_, R> {
         self.resources.get_mut_or_insert::<R>(f).await
     }
@@ -194,55 +386,142 @@ impl World {
 
     /// Spawns an entity with some [`Component`](crate::component::Component)s.
     ///
+    /// Any [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks are run before this returns.
+    ///
     /// # Panics
     /// Panics if the given [`ComponentBundle`] is not valid.
     /// See [`BundleValidator`](crate::component::bundle::BundleValidator).
     #[track_caller]
     pub async fn spawn<B : ComponentBundle + 'static>(self : &Arc<Self>, bundle : B) -> Entity {
-        self.archetypes.spawn::<B>(bundle).await
+        let entity = self.archetypes.spawn::<B>(bundle).await;
+        ArchetypeStorage::run_hooks(self, entity, B::type_info(), ComponentTypeInfo::on_add).await;
+        entity
     }
 
     /// Spawns an entity with some [`Component`](crate::component::Component)s, without checking that the given [`ComponentBundle`] is valid.
     ///
+    /// Any [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks are run before this returns.
+    ///
     /// # Safety
     /// The caller is responsible for ensuring that the given [`ComponentBundle`] does not violate the archetype rules. See [`BundleValidator`](crate::component::bundle::BundleValidator).
     pub async unsafe fn spawn_unchecked<B : ComponentBundle + 'static>(self : &Arc<Self>, bundle : B) -> Entity {
-        unsafe{ self.archetypes.spawn_unchecked::<B>(bundle).await }
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        let entity = unsafe{ self.archetypes.spawn_unchecked::<B>(bundle).await };
+        ArchetypeStorage::run_hooks(self, entity, B::type_info(), ComponentTypeInfo::on_add).await;
+        entity
     }
 
     /// Spawns multiple entities with some [`Component`](crate::component::Component)s.
     ///
     /// This is more efficient than [`World::spawn`], but has the downside of only being able to spawn entities with the same [`ComponentBundle`] type.
+    /// Any [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks are run for each entity, after every entity has been spawned.
     ///
     /// # Panics
     /// Panics if the given [`ComponentBundle`]s are not valid.
     /// See [`BundleValidator`](crate::component::bundle::BundleValidator).
     #[track_caller]
     pub async fn spawn_batch<'l, B : ComponentBundle + 'static>(self : &'l Arc<Self>, bundles : impl IntoIterator<Item = B> + 'l) -> impl Iterator<Item = Entity> {
-        self.archetypes.spawn_batch::<B>(bundles).await
+        let entities = self.archetypes.spawn_batch::<B>(bundles).await.collect::<Vec<_>>();
+        self.run_on_add_hooks::<B>(&entities).await;
+        entities.into_iter()
     }
 
     /// Spawns multiple entities with some [`Component`](crate::component::Component)s, without checking that the given [`ComponentBundle`] is valid.
     ///
     /// This is more efficient than [`World::spawn`], but has the downside of only being able to spawn entities with the same [`ComponentBundle`] type.
+    /// Any [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks are run for each entity, after every entity has been spawned.
     ///
     /// # Safety
     /// The caller is responsible for ensuring that the given [`ComponentBundle`] does not violate the archetype rules. See [`BundleValidator`](crate::component::bundle::BundleValidator).
     pub async unsafe fn spawn_batch_unchecked<'l, B : ComponentBundle + 'static>(self : &'l Arc<Self>, bundles : impl IntoIterator<Item = B> + 'l) -> impl Iterator<Item = Entity> {
-        unsafe{ self.archetypes.spawn_batch_unchecked::<B>(bundles).await }
+        // SAFETY: The caller is responsible for upholding the safety guarantees.
+        let entities = unsafe{ self.archetypes.spawn_batch_unchecked::<B>(bundles).await }.collect::<Vec<_>>();
+        self.run_on_add_hooks::<B>(&entities).await;
+        entities.into_iter()
+    }
+
+    /// Runs the [`Component::ON_ADD`](crate::component::Component::ON_ADD) hooks of `B` for each of the given entities, in order.
+    async fn run_on_add_hooks<B : ComponentBundle>(self : &Arc<Self>, entities : &[Entity]) {
+        let type_infos = B::type_info();
+        for &entity in entities {
+            ArchetypeStorage::run_hooks(self, entity, type_infos.iter().copied(), ComponentTypeInfo::on_add).await;
+        }
     }
 
     /// Removes an entity.
+    ///
+    /// Any [`Component::ON_REMOVE`](crate::component::Component::ON_REMOVE) hooks are run before the entity is removed,
+    ///  so its components can still be queried by them. Despawning the entity again while they run does nothing.
     pub async fn despawn(self : &Arc<Self>, entity : Entity) {
-        self.archetypes.despawn(entity).await
+        self.archetypes.despawn_with_hooks(self, entity).await;
+        #[cfg(not(feature = "no_std"))]
+        self.observers.write().await.remove_entity(entity);
     }
 
     /// Removes an entity without checking that it exists.
     ///
+    /// Any [`Component::ON_REMOVE`](crate::component::Component::ON_REMOVE) hooks are run before the entity is removed.
+    ///
     /// # Safety
     /// You are responsible for ensuring that the given entity exists.
     pub async unsafe fn despawn_unchecked(self : &Arc<Self>, entity : Entity) {
-        self.archetypes.despawn_unchecked(entity).await
+        self.archetypes.despawn_with_hooks(self, entity).await;
+        #[cfg(not(feature = "no_std"))]
+        self.observers.write().await.remove_entity(entity);
+    }
+
+
+    /// Attaches an observer system to an entity.
+    ///
+    /// The system takes a [`Trigger<E>`] as its first parameter, and runs each time an event of type `E` is triggered on the entity.
+    /// It is detached when the entity is despawned. See [`Trigger`] for an example.
+    #[cfg(not(feature = "no_std"))]
+    #[track_caller]
+    pub async fn observe<E : Event + 'static, S : IntoSystem<Params, ()>, Params>(self : &Arc<Self>, entity : Entity, system : S)
+    where S::System : System<(), Passed = Trigger<E>> + Send + Sync + 'static
+    {
+        let system = system.into_system(Arc::clone(self), Some(SystemId::unique()));
+        self.observers.write().await.insert::<E>(entity, Box::new(system));
+    }
+
+    /// Triggers an event on an entity, running each of its observer systems for events of type `E` and waiting for them to finish.
+    ///
+    /// Observer systems are not run in parallel.
+    /// An observer system which is already running, such as one which triggered this event itself, is not waited for.
+    ///  Instead it is run again once the current systems have finished.
+    /// See [`EventWriter::trigger`](crate::query::EventWriter::trigger) to trigger an event from a system.
+    #[cfg(not(feature = "no_std"))]
+    pub async fn trigger<E : Event + Send + Sync + 'static>(self : &Arc<Self>, entity : Entity, event : E) {
+        let observers = self.observers.read().await.get::<E>(entity);
+        for observer in observers {
+            let trigger = Trigger::new(entity, event.clone());
+            if let Some(mut system) = observer.try_lock() {
+                // SAFETY: The system was validated when it was attached in `World::observe`.
+                unsafe{ system.acquire_and_run(trigger, Arc::clone(self)) }.await;
+            } else {
+                // Waiting for the running observer here would deadlock if this was triggered from inside of it.
+                self.deferred_cmd_queue.write().await.push(Box::new(move |world| Box::pin(async move {
+                    let mut system = observer.lock().await;
+                    // SAFETY: The system was validated when it was attached in `World::observe`.
+                    unsafe{ system.acquire_and_run(trigger, world) }.await;
+                })));
+            }
+        }
+    }
+
+
+    /// Returns an [`EventSender`] which sends events of type `E` into this world from outside of it, such as from another thread or task.
+    ///
+    /// See [`App::event_sender`](crate::app::App::event_sender) to get one before the app is run.
+    pub async fn event_sender<E : Event + 'static>(&self) -> EventSender<E> {
+        EventSender::from_world(self).await
+    }
+
+    /// Returns an [`EventReceiver`] which receives events of type `E` sent in this world from outside of it, such as from another thread or task.
+    ///
+    /// See [`App::event_receiver`](crate::app::App::event_receiver) to get one before the app is run.
+    pub async fn event_receiver<E : Event + 'static>(&self) -> EventReceiver<E> {
+        EventReceiver::from_world(self).await
     }
 
 
@@ -0,0 +1,207 @@
+//! Systems which run when an entity is targeted by an event.
+
+
+use crate::entity::Entity;
+use crate::query::Event;
+use crate::system::SystemPassable;
+use crate::schedule::system::TypeErasedSystem;
+use core::any::{ Any, TypeId };
+use core::ops::Deref;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+use alloc::collections::BTreeMap;
+use async_std::sync::Mutex;
+
+
+/// An [`Event`] targeted at an [`Entity`], passed to observer systems.
+///
+/// Observer systems take this as their first parameter, and are attached to an entity with
+///  [`World::observe`](crate::world::World::observe) or [`Commands::observe`](crate::world::Commands::observe).
+/// They run whenever an event of type `E` is triggered on that entity.
+///
+/// ### Examples
+/// ```rust
+/// use axecs::prelude::*;
+/// use std::sync::Arc;
+///
+/// #[derive(Event, Clone)]
+/// struct OnClick;
+///
+/// #[derive(Component)]
+/// struct Button;
+///
+/// async fn spawn_button(world : &Arc<World>) {
+///     let button = world.spawn((Button,)).await;
+///     world.observe(button, async |trigger : Trigger<OnClick>| {
+///         println!("Clicked button {}", trigger.target().archetype_row());
+///     }).await;
+/// }
+///
+/// async fn click_button(
+///     clicks : EventWriter<OnClick>,
+///     button : Entities<Entity, With<Button>>
+/// ) {
+///     for entity in &button {
+///         clicks.trigger(entity, OnClick).await;
+///     }
+/// }
+/// ```
+pub struct Trigger<E : Event> {
+
+    /// The [`Entity`] that the event was triggered on.
+    target : Entity,
+
+    /// The event.
+    event  : E
+
+}
+
+impl<E : Event> Trigger<E> {
+
+    /// Creates a new [`Trigger`].
+    pub(crate) fn new(target : Entity, event : E) -> Self { Self {
+        target,
+        event
+    } }
+
+    /// Returns the [`Entity`] that the event was triggered on.
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// Returns a reference to the event.
+    pub fn event(&self) -> &E {
+        &self.event
+    }
+
+    /// Returns the event, consuming this [`Trigger`].
+    pub fn into_event(self) -> E {
+        self.event
+    }
+
+}
+
+impl<E : Event> SystemPassable for Trigger<E> { }
+
+impl<E : Event> Deref for Trigger<E> {
+    type Target = E;
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+
+/// A type-erased observer system for events of type `E`.
+pub(crate) type Observer<E> = Mutex<Box<dyn TypeErasedSystem<Trigger<E>, ()> + Send + Sync>>;
+
+
+/// The observer systems attached to each [`Entity`] in a [`World`](crate::world::World), by event type.
+pub(crate) struct ObserverStorage {
+
+    /// The [`Observer`]s, by target entity and the [`TypeId`] of the event type.
+    observers : BTreeMap<(Entity, TypeId), Vec<Arc<dyn Any + Send + Sync>>>
+
+}
+
+impl ObserverStorage {
+
+    /// Creates an empty [`ObserverStorage`].
+    pub(crate) fn new() -> Self { Self {
+        observers : BTreeMap::new()
+    } }
+
+    /// Attaches an observer system to an [`Entity`].
+    pub(crate) fn insert<E : Event + 'static>(&mut self, entity : Entity, system : Box<dyn TypeErasedSystem<Trigger<E>, ()> + Send + Sync>) {
+        self.observers.entry((entity, TypeId::of::<E>())).or_default().push(Arc::new(Mutex::new(system)));
+    }
+
+    /// Returns the observer systems attached to an [`Entity`] for events of type `E`.
+    pub(crate) fn get<E : Event + 'static>(&self, entity : Entity) -> Vec<Arc<Observer<E>>> {
+        self.observers.get(&(entity, TypeId::of::<E>())).map_or_else(Vec::new, |observers| observers.iter()
+            // SAFETY: Observers are stored under the `TypeId` of their event type.
+            .map(|observer| unsafe{ Arc::clone(observer).downcast::<Observer<E>>().unwrap_unchecked() })
+            .collect()
+        )
+    }
+
+    /// Detaches every observer system from an [`Entity`].
+    pub(crate) fn remove_entity(&mut self, entity : Entity) {
+        self.observers.retain(|(target, _), _| *target != entity);
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{ World, WorldMut };
+    use crate::component::Component;
+    use core::mem;
+    use core::sync::atomic::{ AtomicUsize, Ordering };
+    use async_std::task::block_on;
+
+    #[derive(Clone)]
+    struct Ping(usize);
+    impl Event for Ping { }
+
+    #[derive(Clone)]
+    struct Pong;
+    impl Event for Pong { }
+
+    struct Target;
+    impl Component for Target { }
+
+    #[test]
+    fn triggers_run_observers_of_target() { block_on(async {
+        static PINGS : AtomicUsize = AtomicUsize::new(0);
+        let world = Arc::new(World::new());
+        let a     = world.spawn((Target,)).await;
+        let b     = world.spawn((Target,)).await;
+        world.observe(a, async |trigger : Trigger<Ping>| { PINGS.fetch_add(trigger.0, Ordering::Relaxed); }).await;
+        world.observe(a, async |trigger : Trigger<Ping>| { PINGS.fetch_add(trigger.0 * 10, Ordering::Relaxed); }).await;
+        world.trigger(a, Ping(1)).await;
+        assert_eq!(PINGS.load(Ordering::Relaxed), 11);
+        // Observers only run for their own entity and event type.
+        world.trigger(b, Ping(1)).await;
+        world.trigger(a, Pong).await;
+        assert_eq!(PINGS.load(Ordering::Relaxed), 11);
+    }) }
+
+    #[test]
+    fn despawn_detaches_observers() { block_on(async {
+        static PINGS : AtomicUsize = AtomicUsize::new(0);
+        let world  = Arc::new(World::new());
+        let entity = world.spawn((Target,)).await;
+        world.observe(entity, async |_trigger : Trigger<Ping>| { PINGS.fetch_add(1, Ordering::Relaxed); }).await;
+        world.observe(entity, async |_trigger : Trigger<Pong>| { PINGS.fetch_add(1, Ordering::Relaxed); }).await;
+        world.despawn(entity).await;
+        assert!(world.observers.read().await.observers.is_empty());
+        world.trigger(entity, Ping(0)).await;
+        assert_eq!(PINGS.load(Ordering::Relaxed), 0);
+    }) }
+
+    #[test]
+    fn reentrant_triggers_are_deferred() { block_on(async {
+        static PINGS : AtomicUsize = AtomicUsize::new(0);
+        let world  = Arc::new(World::new());
+        let entity = world.spawn((Target,)).await;
+        world.observe(entity, async |trigger : Trigger<Ping>, world : WorldMut| {
+            PINGS.fetch_add(1, Ordering::Relaxed);
+            if (trigger.0 > 0) {
+                world.trigger(trigger.target(), Ping(trigger.0 - 1)).await;
+            }
+        }).await;
+        world.trigger(entity, Ping(1)).await;
+        assert_eq!(PINGS.load(Ordering::Relaxed), 1);
+        let cmds = mem::take(&mut *world.deferred_cmd_queue.write().await);
+        assert_eq!(cmds.len(), 1);
+        for cmd in cmds {
+            cmd(Arc::clone(&world)).await;
+        }
+        assert_eq!(PINGS.load(Ordering::Relaxed), 2);
+    }) }
+
+}